@@ -2,8 +2,15 @@
 // Hybrid Approach: Admin PDAs + Employee Merkle Tree
 
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::ed25519_program;
 use anchor_lang::solana_program::keccak;
+use anchor_lang::solana_program::sysvar::instructions::{
+    self as instructions_sysvar, load_current_index_checked, load_instruction_at_checked,
+};
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
 
+// Replace with the deployed program ID; initialize_platform checks its
+// upgrade authority.
 declare_id!("YourProgramIDHere111111111111111111111111111");
 
 // ============================================================================
@@ -13,13 +20,51 @@ declare_id!("YourProgramIDHere111111111111111111111111111");
 const MAX_COMPANY_NAME_LEN: usize = 32;
 const MAX_MARKET_TITLE_LEN: usize = 64;
 const MAX_MARKET_DESC_LEN: usize = 128;
+const MAX_METADATA_URI_LEN: usize = 200;
+const MAX_OUTCOMES: usize = 8;
+const MAX_OUTCOME_LABEL_LEN: usize = 32;
+const MAX_RESOLVERS: usize = 5;
+const MAX_COMPANY_SIGNERS: usize = 5;
+const MAX_GUARDIANS: usize = 3;
+const ORACLE_REPORT_LEN: usize = 48; // market (32) + value (8) + timestamp (8)
+const SCALAR_SHORT: usize = 0;
+const SCALAR_LONG: usize = 1;
+const FP_SCALE: u128 = 1_000_000_000_000; // 1e12 fixed-point for LMSR math
+const LN_2_FP: u128 = 693_147_180_560; // ln(2) * 1e12
+const PRICE_SCALE: u64 = 1_000_000_000; // Implied probabilities sum to 1e9
+const BPS_DENOMINATOR: u64 = 10_000;
+const MAX_EARLY_EXIT_FEE_BPS: u16 = 2_000; // 20%
+const MAX_SETTLEMENT_FEE_BPS: u16 = 1_000; // 10%
+const MAX_FEE_OVERRIDES: usize = 16;
 const MAX_ADMINS_PER_COMPANY: u16 = 100;
 const MAX_MERKLE_PROOF_DEPTH: usize = 24; // Supports 16M employees
-const COMPANY_REGISTRATION_FEE: u64 = 100_000_000; // 0.1 SOL
-const ADMIN_GRANT_FEE: u64 = 5_000_000; // 0.005 SOL
+const MAX_COMPANY_REGISTRATION_FEE: u64 = 10_000_000_000; // 10 SOL
+const MAX_ADMIN_GRANT_FEE: u64 = 1_000_000_000; // 1 SOL
 const RATE_LIMIT_WINDOW: i64 = 3600; // 1 hour
 const MAX_MARKETS_PER_HOUR: u16 = 50;
 
+// AdminRole.permissions bits (company authority implicitly holds all)
+const PERM_CREATE_MARKET: u16 = 1 << 0;
+const PERM_RESOLVE_MARKET: u16 = 1 << 1;
+const PERM_GRANT_ROLE: u16 = 1 << 2;
+const PERM_REVOKE_ROLE: u16 = 1 << 3;
+const PERM_PAUSE: u16 = 1 << 4;
+const PERM_MANAGE_TREASURY: u16 = 1 << 5;
+const PERM_UPDATE_ROOT: u16 = 1 << 6;
+const PERM_ALL: u16 = (1 << 7) - 1;
+
+const DEFAULT_DISPUTE_WINDOW: i64 = 86_400; // 24 hours
+const MIN_DISPUTE_WINDOW: i64 = 3_600; // 1 hour
+const MAX_DISPUTE_WINDOW: i64 = 604_800; // 7 days
+const MAX_TIMELOCK_DELAY: i64 = 2_592_000; // 30 days
+
+// Company.pause_flags scopes
+const PAUSE_BETTING: u8 = 1 << 0; // place/withdraw bets, buy/sell shares
+const PAUSE_CREATION: u8 = 1 << 1; // create, edit and open markets
+const PAUSE_RESOLUTION: u8 = 1 << 2; // propose, dispute, vote, oracle, finalize
+const PAUSE_CLAIMS: u8 = 1 << 3; // claim winnings, refunds, dust sweeps
+const PAUSE_ALL: u8 = (1 << 4) - 1;
+
 // ============================================================================
 // PROGRAM MODULE
 // ============================================================================
@@ -28,6 +73,170 @@ const MAX_MARKETS_PER_HOUR: u16 = 50;
 pub mod prediction_market_rbac {
     use super::*;
 
+    // ------------------------------------------------------------------------
+    // PLATFORM GOVERNANCE
+    // ------------------------------------------------------------------------
+
+    /// One-time setup, restricted to the program's upgrade authority so the
+    /// config cannot be front-run after deployment.
+    pub fn initialize_platform(
+        ctx: Context<InitializePlatform>,
+        treasury: Pubkey,
+        company_registration_fee: u64,
+        admin_grant_fee: u64,
+    ) -> Result<()> {
+        validate_fee_schedule(company_registration_fee, admin_grant_fee)?;
+
+        let platform_config = &mut ctx.accounts.platform_config;
+        platform_config.admin = ctx.accounts.admin.key();
+        platform_config.pending_admin = None;
+        platform_config.treasury = treasury;
+        platform_config.company_registration_fee = company_registration_fee;
+        platform_config.admin_grant_fee = admin_grant_fee;
+        platform_config.default_fees = FeeSplit::default();
+        platform_config.fee_overrides = Vec::new();
+        platform_config.paused = false;
+        platform_config.bump = ctx.bumps.platform_config;
+
+        emit!(PlatformConfigUpdated {
+            admin: platform_config.admin,
+            treasury,
+            company_registration_fee,
+            admin_grant_fee,
+        });
+
+        Ok(())
+    }
+
+    pub fn update_platform_config(
+        ctx: Context<UpdatePlatformConfig>,
+        treasury: Pubkey,
+        company_registration_fee: u64,
+        admin_grant_fee: u64,
+    ) -> Result<()> {
+        validate_fee_schedule(company_registration_fee, admin_grant_fee)?;
+
+        let platform_config = &mut ctx.accounts.platform_config;
+        platform_config.treasury = treasury;
+        platform_config.company_registration_fee = company_registration_fee;
+        platform_config.admin_grant_fee = admin_grant_fee;
+
+        emit!(PlatformConfigUpdated {
+            admin: platform_config.admin,
+            treasury,
+            company_registration_fee,
+            admin_grant_fee,
+        });
+
+        Ok(())
+    }
+
+    /// Global kill switch. Every instruction outside platform governance
+    /// rejects while set.
+    pub fn set_platform_paused(ctx: Context<UpdatePlatformConfig>, paused: bool) -> Result<()> {
+        let platform_config = &mut ctx.accounts.platform_config;
+        platform_config.paused = paused;
+
+        emit!(PlatformPaused {
+            paused,
+            admin: ctx.accounts.admin.key(),
+        });
+
+        Ok(())
+    }
+
+    /// Two-step like company authority transfer; `None` cancels.
+    pub fn propose_platform_admin(
+        ctx: Context<UpdatePlatformConfig>,
+        new_admin: Option<Pubkey>,
+    ) -> Result<()> {
+        let platform_config = &mut ctx.accounts.platform_config;
+        if let Some(new_admin) = new_admin {
+            require!(
+                new_admin != platform_config.admin && new_admin != Pubkey::default(),
+                ErrorCode::InvalidAuthority
+            );
+        }
+
+        platform_config.pending_admin = new_admin;
+
+        emit!(PlatformAdminProposed {
+            admin: platform_config.admin,
+            pending_admin: new_admin,
+        });
+
+        Ok(())
+    }
+
+    pub fn accept_platform_admin(ctx: Context<AcceptPlatformAdmin>) -> Result<()> {
+        let platform_config = &mut ctx.accounts.platform_config;
+        let previous_admin = platform_config.admin;
+
+        platform_config.admin = ctx.accounts.new_admin.key();
+        platform_config.pending_admin = None;
+
+        emit!(PlatformAdminTransferred {
+            previous_admin,
+            new_admin: platform_config.admin,
+        });
+
+        Ok(())
+    }
+
+    /// Applies to markets created afterwards; existing markets keep the
+    /// split snapshotted at creation.
+    pub fn update_fee_schedule(
+        ctx: Context<UpdatePlatformConfig>,
+        default_fees: FeeSplit,
+    ) -> Result<()> {
+        validate_fee_split(&default_fees)?;
+
+        ctx.accounts.platform_config.default_fees = default_fees;
+
+        emit!(FeeScheduleUpdated {
+            company: None,
+            fees: Some(default_fees),
+        });
+
+        Ok(())
+    }
+
+    /// `None` removes the override so the company falls back to the default.
+    pub fn set_company_fee_override(
+        ctx: Context<UpdatePlatformConfig>,
+        company: Pubkey,
+        fees: Option<FeeSplit>,
+    ) -> Result<()> {
+        let overrides = &mut ctx.accounts.platform_config.fee_overrides;
+        let existing = overrides.iter().position(|o| o.company == company);
+
+        match (fees, existing) {
+            (Some(fees), Some(i)) => {
+                validate_fee_split(&fees)?;
+                overrides[i].fees = fees;
+            }
+            (Some(fees), None) => {
+                validate_fee_split(&fees)?;
+                require!(
+                    overrides.len() < MAX_FEE_OVERRIDES,
+                    ErrorCode::TooManyFeeOverrides
+                );
+                overrides.push(CompanyFeeOverride { company, fees });
+            }
+            (None, Some(i)) => {
+                overrides.swap_remove(i);
+            }
+            (None, None) => return err!(ErrorCode::NoFeeOverride),
+        }
+
+        emit!(FeeScheduleUpdated {
+            company: Some(company),
+            fees,
+        });
+
+        Ok(())
+    }
+
     // ------------------------------------------------------------------------
     // COMPANY MANAGEMENT
     // ------------------------------------------------------------------------
@@ -45,7 +254,7 @@ pub mod prediction_market_rbac {
             &anchor_lang::solana_program::system_instruction::transfer(
                 &ctx.accounts.authority.key(),
                 &ctx.accounts.platform_treasury.key(),
-                COMPANY_REGISTRATION_FEE,
+                ctx.accounts.platform_config.company_registration_fee,
             ),
             &[
                 ctx.accounts.authority.to_account_info(),
@@ -61,8 +270,18 @@ pub mod prediction_market_rbac {
         company.employee_merkle_root = employee_merkle_root;
         company.employee_root_version = 1;
         company.created_at = Clock::get()?.unix_timestamp;
-        company.paused = false;
+        company.pause_flags = 0;
         company.total_markets = 0;
+        company.dispute_window = DEFAULT_DISPUTE_WINDOW;
+        company.resolution_bond = 0;
+        company.pending_authority = None;
+        company.signers = Vec::new();
+        company.signer_threshold = 0;
+        company.signer_set_version = 0;
+        company.proposal_count = 0;
+        company.timelock_delay = 0;
+        company.guardians = Vec::new();
+        company.pause_guardians = Vec::new();
         company.bump = ctx.bumps.company;
 
         emit!(CompanyRegistered {
@@ -74,33 +293,488 @@ pub mod prediction_market_rbac {
         Ok(())
     }
 
+    /// In threshold mode root updates go through a CompanyProposal, so no
+    /// single key, authority or UpdateRoot admin, can replace the root.
     pub fn update_employee_merkle_root(
         ctx: Context<UpdateEmployeeMerkleRoot>,
         new_root: [u8; 32],
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.company.signer_threshold == 0,
+            ErrorCode::ProposalRequired
+        );
+        require!(
+            has_sensitive_permission(
+                &ctx.accounts.company,
+                &ctx.accounts.authority.key(),
+                &ctx.accounts.admin_role,
+                PERM_UPDATE_ROOT
+            ),
+            ErrorCode::MissingPermission
+        );
+        require!(
+            ctx.accounts.company.timelock_delay == 0,
+            ErrorCode::TimelockRequired
+        );
+
+        apply_employee_root_update(&mut ctx.accounts.company, new_root)
+    }
+
+    /// Timelock guardians, pause guardians, the authority and admins with
+    /// Pause can set pause flags. Only ever adds flags, so replaying it
+    /// cannot unpause anything.
+    pub fn pause(ctx: Context<PauseCompany>, flags: u8, reason: u16) -> Result<()> {
+        let pauser = ctx.accounts.pauser.key();
+        let company = &ctx.accounts.company;
+        require!(
+            company.guardians.contains(&pauser)
+                || company.pause_guardians.contains(&pauser)
+                || has_permission(
+                    &ctx.accounts.company,
+                    &pauser,
+                    &ctx.accounts.admin_role,
+                    PERM_PAUSE
+                ),
+            ErrorCode::MissingPermission
+        );
+        require!(
+            flags != 0 && flags & !PAUSE_ALL == 0,
+            ErrorCode::InvalidPauseFlags
+        );
+
+        let company = &mut ctx.accounts.company;
+        company.pause_flags |= flags;
+
+        emit!(CompanyPaused {
+            company: company.key(),
+            flags,
+            pause_flags: company.pause_flags,
+            reason,
+            paused_by: pauser,
+        });
+
+        Ok(())
+    }
+
+    /// Guardians cannot unpause. In threshold mode the authority key must
+    /// use an Unpause proposal.
+    pub fn unpause(ctx: Context<UnpauseCompany>, flags: u8, reason: u16) -> Result<()> {
+        let unpauser = ctx.accounts.unpauser.key();
+        require!(
+            has_sensitive_permission(
+                &ctx.accounts.company,
+                &unpauser,
+                &ctx.accounts.admin_role,
+                PERM_PAUSE
+            ),
+            ErrorCode::MissingPermission
+        );
+
+        apply_unpause(&mut ctx.accounts.company, flags, reason, unpauser)
+    }
+
+    /// Pause guardians can only pause: unlike timelock guardians they cannot
+    /// cancel queued actions, so they are not timelocked and can be changed
+    /// at any time (by proposal in threshold mode).
+    pub fn set_pause_guardians(
+        ctx: Context<SetPauseGuardians>,
+        guardians: Vec<Pubkey>,
+    ) -> Result<()> {
+        let company = &mut ctx.accounts.company;
+        require!(company.signer_threshold == 0, ErrorCode::ProposalRequired);
+
+        apply_pause_guardians(company, guardians)
+    }
+
+    pub fn update_resolution_config(
+        ctx: Context<UpdateResolutionConfig>,
+        dispute_window: i64,
+        resolution_bond: u64,
+    ) -> Result<()> {
+        let company = &mut ctx.accounts.company;
+        require!(company.signer_threshold == 0, ErrorCode::ProposalRequired);
+
+        apply_resolution_config(company, dispute_window, resolution_bond)
+    }
+
+    /// First half of a two-step handover. Nothing changes until the new key
+    /// signs accept_authority_transfer, so a mistyped key cannot lock the
+    /// company out.
+    pub fn propose_authority_transfer(
+        ctx: Context<ProposeAuthorityTransfer>,
+        new_authority: Pubkey,
+    ) -> Result<()> {
+        let company = &mut ctx.accounts.company;
+        require!(company.signer_threshold == 0, ErrorCode::ProposalRequired);
+
+        apply_pending_authority(company, Some(new_authority))
+    }
+
+    pub fn accept_authority_transfer(ctx: Context<AcceptAuthorityTransfer>) -> Result<()> {
+        let company = &mut ctx.accounts.company;
+        let previous_authority = company.authority;
+
+        company.authority = ctx.accounts.new_authority.key();
+        company.pending_authority = None;
+
+        emit!(AuthorityTransferred {
+            company: company.key(),
+            previous_authority,
+            new_authority: company.authority,
+        });
+
+        Ok(())
+    }
+
+    pub fn cancel_authority_transfer(ctx: Context<CancelAuthorityTransfer>) -> Result<()> {
+        let company = &mut ctx.accounts.company;
+        require!(company.signer_threshold == 0, ErrorCode::ProposalRequired);
+
+        apply_pending_authority(company, None)
+    }
+
+    // ------------------------------------------------------------------------
+    // THRESHOLD AUTHORITY (CompanyProposal PDAs)
+    // ------------------------------------------------------------------------
+
+    /// Switches a single-key company to threshold mode. From then on root
+    /// updates, unpausing, revocation, authority transfer and signer set
+    /// changes by the authority key must go through a CompanyProposal.
+    /// Admin roles keep their own permissions.
+    pub fn configure_signer_set(
+        ctx: Context<ConfigureSignerSet>,
+        signers: Vec<Pubkey>,
+        threshold: u8,
+    ) -> Result<()> {
+        let company = &mut ctx.accounts.company;
+        require!(company.signer_threshold == 0, ErrorCode::ProposalRequired);
+        require!(threshold > 0, ErrorCode::InvalidSignerSet);
+
+        apply_signer_set(company, signers, threshold)
+    }
+
+    /// The proposer's approval is recorded immediately.
+    pub fn create_company_proposal(
+        ctx: Context<CreateCompanyProposal>,
+        action: CompanyAction,
     ) -> Result<()> {
         let company = &mut ctx.accounts.company;
+        require!(company.signer_threshold > 0, ErrorCode::NoSignerSet);
+
+        let proposer = ctx.accounts.proposer.key();
+        let signer_index = signer_index(company, &proposer)?;
+        validate_company_action(company, &action)?;
+        let now = Clock::get()?.unix_timestamp;
+
+        let proposal = &mut ctx.accounts.proposal;
+        proposal.company = company.key();
+        proposal.proposal_id = company.proposal_count;
+        proposal.proposer = proposer;
+        proposal.action = action;
+        proposal.approvals = 1 << signer_index;
+        proposal.approval_count = 1;
+        proposal.signer_set_version = company.signer_set_version;
+        proposal.created_at = now;
+        proposal.eta = None;
+        proposal.bump = ctx.bumps.proposal;
+
+        company.proposal_count = company
+            .proposal_count
+            .checked_add(1)
+            .ok_or(ErrorCode::Overflow)?;
+
+        emit!(CompanyProposalCreated {
+            company: company.key(),
+            proposal: proposal.key(),
+            proposal_id: proposal.proposal_id,
+            proposer,
+        });
+
+        start_timelock_if_approved(company, proposal, now)
+    }
+
+    pub fn approve_company_proposal(ctx: Context<ApproveCompanyProposal>) -> Result<()> {
+        let company = &ctx.accounts.company;
+        let proposal = &mut ctx.accounts.proposal;
+        require!(
+            proposal.signer_set_version == company.signer_set_version,
+            ErrorCode::StaleProposal
+        );
+
+        let approver = ctx.accounts.approver.key();
+        let bit = 1u8 << signer_index(company, &approver)?;
+        require!(proposal.approvals & bit == 0, ErrorCode::AlreadyApproved);
 
-        company.employee_merkle_root = new_root;
-        company.employee_root_version = company
-            .employee_root_version
+        proposal.approvals |= bit;
+        proposal.approval_count = proposal
+            .approval_count
             .checked_add(1)
             .ok_or(ErrorCode::Overflow)?;
 
-        emit!(EmployeeMerkleRootUpdated {
+        emit!(CompanyProposalApproved {
             company: company.key(),
-            new_version: company.employee_root_version,
+            proposal: proposal.key(),
+            approver,
+            approval_count: proposal.approval_count,
+        });
+
+        start_timelock_if_approved(company, proposal, Clock::get()?.unix_timestamp)
+    }
+
+    /// Permissionless once the threshold is met and, for timelocked actions,
+    /// the eta has passed. The proposal account is closed back to the
+    /// proposer.
+    pub fn execute_company_proposal(ctx: Context<ExecuteCompanyProposal>) -> Result<()> {
+        let proposal = &ctx.accounts.proposal;
+        require_executable(&ctx.accounts.company, proposal)?;
+        let proposal_key = proposal.key();
+        let proposal_id = proposal.proposal_id;
+        let action = proposal.action.clone();
+
+        // Re-check against current state; it may have moved since proposal
+        validate_company_action(&ctx.accounts.company, &action)?;
+
+        match action {
+            CompanyAction::UpdateEmployeeMerkleRoot { new_root } => {
+                apply_employee_root_update(&mut ctx.accounts.company, new_root)?;
+            }
+            CompanyAction::Unpause { flags, reason } => {
+                let company_key = ctx.accounts.company.key();
+                apply_unpause(&mut ctx.accounts.company, flags, reason, company_key)?;
+            }
+            CompanyAction::RevokeAdminRole { user, close_account } => {
+                let company_key = ctx.accounts.company.key();
+                let admin_role = ctx
+                    .accounts
+                    .admin_role
+                    .as_mut()
+                    .ok_or(ErrorCode::AdminRoleMismatch)?;
+                require!(
+                    admin_role.user == user && admin_role.company == company_key,
+                    ErrorCode::AdminRoleMismatch
+                );
+
+                // The signer set acts as the company itself
+                apply_admin_role_revocation(
+                    &mut ctx.accounts.company,
+                    admin_role,
+                    company_key,
+                    close_account,
+                )?;
+                if close_account {
+                    admin_role.close(ctx.accounts.company_authority.to_account_info())?;
+                }
+            }
+            CompanyAction::SetPendingAuthority { pending_authority } => {
+                apply_pending_authority(&mut ctx.accounts.company, pending_authority)?;
+            }
+            CompanyAction::UpdateSignerSet { signers, threshold } => {
+                apply_signer_set(&mut ctx.accounts.company, signers, threshold)?;
+            }
+            CompanyAction::UpdateTimelockConfig { delay, guardians } => {
+                apply_timelock_config(&mut ctx.accounts.company, delay, guardians)?;
+            }
+            CompanyAction::UpdateResolutionConfig {
+                dispute_window,
+                resolution_bond,
+            } => {
+                apply_resolution_config(&mut ctx.accounts.company, dispute_window, resolution_bond)?;
+            }
+            // The role PDA has to be created, which needs its own context
+            CompanyAction::GrantAdminRole { .. } => {
+                return err!(ErrorCode::WrongExecutionPath);
+            }
+            CompanyAction::RegrantAdminRole {
+                user,
+                permissions,
+                expires_at,
+            } => {
+                let company_key = ctx.accounts.company.key();
+                let admin_role = ctx
+                    .accounts
+                    .admin_role
+                    .as_mut()
+                    .ok_or(ErrorCode::AdminRoleMismatch)?;
+                require!(
+                    admin_role.user == user && admin_role.company == company_key,
+                    ErrorCode::AdminRoleMismatch
+                );
+
+                apply_admin_role_regrant(
+                    &mut ctx.accounts.company,
+                    admin_role,
+                    company_key,
+                    permissions,
+                    expires_at,
+                )?;
+            }
+            CompanyAction::SetPauseGuardians { guardians } => {
+                apply_pause_guardians(&mut ctx.accounts.company, guardians)?;
+            }
+            CompanyAction::RenewAdminRole { user, expires_at } => {
+                let company_key = ctx.accounts.company.key();
+                let admin_role = ctx
+                    .accounts
+                    .admin_role
+                    .as_mut()
+                    .ok_or(ErrorCode::AdminRoleMismatch)?;
+                require!(
+                    admin_role.user == user && admin_role.company == company_key,
+                    ErrorCode::AdminRoleMismatch
+                );
+
+                apply_admin_role_renewal(admin_role, company_key, expires_at)?;
+            }
+        }
+
+        emit!(CompanyProposalExecuted {
+            company: ctx.accounts.company.key(),
+            proposal: proposal_key,
+            proposal_id,
+            executed_by: ctx.accounts.executor.key(),
         });
 
         Ok(())
     }
 
-    pub fn toggle_pause(ctx: Context<TogglePause>) -> Result<()> {
+    /// Executes a GrantAdminRole proposal, creating the recipient's role
+    /// PDA. No grant fee: the signer set already had to approve it. The
+    /// payer funds the role's rent.
+    pub fn execute_grant_proposal(ctx: Context<ExecuteGrantProposal>) -> Result<()> {
+        let proposal = &ctx.accounts.proposal;
+        require_executable(&ctx.accounts.company, proposal)?;
+
+        let (user, permissions, expires_at) = match proposal.action {
+            CompanyAction::GrantAdminRole {
+                user,
+                permissions,
+                expires_at,
+            } => (user, permissions, expires_at),
+            _ => return err!(ErrorCode::WrongExecutionPath),
+        };
+        require!(
+            user == ctx.accounts.recipient.key(),
+            ErrorCode::AdminRoleMismatch
+        );
+        validate_role_terms(permissions, expires_at, Clock::get()?.unix_timestamp)?;
+
+        let company_key = ctx.accounts.company.key();
+        apply_admin_role_grant(
+            &mut ctx.accounts.company,
+            &mut ctx.accounts.admin_role,
+            user,
+            company_key,
+            permissions,
+            expires_at,
+            ctx.bumps.admin_role,
+        )?;
+
+        emit!(CompanyProposalExecuted {
+            company: company_key,
+            proposal: ctx.accounts.proposal.key(),
+            proposal_id: ctx.accounts.proposal.proposal_id,
+            executed_by: ctx.accounts.payer.key(),
+        });
+
+        Ok(())
+    }
+
+    // ------------------------------------------------------------------------
+    // TIMELOCK (queued CompanyProposals with an eta)
+    // ------------------------------------------------------------------------
+
+    /// Enables the timelock. Once a delay is set, later changes are
+    /// themselves timelocked and go through queue_company_action (or a
+    /// proposal in threshold mode).
+    pub fn configure_timelock(
+        ctx: Context<ConfigureTimelock>,
+        delay: i64,
+        guardians: Vec<Pubkey>,
+    ) -> Result<()> {
         let company = &mut ctx.accounts.company;
-        company.paused = !company.paused;
+        require!(company.signer_threshold == 0, ErrorCode::ProposalRequired);
+        require!(company.timelock_delay == 0, ErrorCode::TimelockRequired);
 
-        emit!(CompanyPaused {
+        apply_timelock_config(company, delay, guardians)
+    }
+
+    /// Queues a timelocked action outside threshold proposals: root updates
+    /// and revocations by anyone holding the matching permission, timelock
+    /// changes by the single-key authority. Executes through
+    /// execute_company_proposal once the eta passes.
+    pub fn queue_company_action(
+        ctx: Context<QueueCompanyAction>,
+        action: CompanyAction,
+    ) -> Result<()> {
+        let queuer = ctx.accounts.queuer.key();
+        let company = &ctx.accounts.company;
+        let permitted = match &action {
+            CompanyAction::UpdateEmployeeMerkleRoot { .. } => has_sensitive_permission(
+                company,
+                &queuer,
+                &ctx.accounts.admin_role,
+                PERM_UPDATE_ROOT,
+            ),
+            CompanyAction::RevokeAdminRole { .. } => has_sensitive_permission(
+                company,
+                &queuer,
+                &ctx.accounts.admin_role,
+                PERM_REVOKE_ROLE,
+            ),
+            CompanyAction::UpdateTimelockConfig { .. } => {
+                require!(company.signer_threshold == 0, ErrorCode::ProposalRequired);
+                queuer == company.authority
+            }
+            _ => return err!(ErrorCode::NotTimelocked),
+        };
+        require!(permitted, ErrorCode::MissingPermission);
+        validate_company_action(company, &action)?;
+
+        let now = Clock::get()?.unix_timestamp;
+        let company = &mut ctx.accounts.company;
+        let proposal = &mut ctx.accounts.proposal;
+        proposal.company = company.key();
+        proposal.proposal_id = company.proposal_count;
+        proposal.proposer = queuer;
+        proposal.action = action;
+        proposal.approvals = 0;
+        proposal.approval_count = 0;
+        proposal.signer_set_version = company.signer_set_version;
+        proposal.created_at = now;
+        proposal.eta = None;
+        proposal.bump = ctx.bumps.proposal;
+
+        company.proposal_count = company
+            .proposal_count
+            .checked_add(1)
+            .ok_or(ErrorCode::Overflow)?;
+
+        start_timelock_if_approved(company, proposal, now)
+    }
+
+    /// Guardians can cancel a queued action whose timelock has started. In
+    /// single-key mode the authority can cancel anything; in threshold mode
+    /// the authority key has no veto (it must not be able to block its own
+    /// rotation) and only the proposer can withdraw a proposal that is still
+    /// collecting approvals.
+    pub fn cancel_company_action(ctx: Context<CancelCompanyAction>) -> Result<()> {
+        let company = &ctx.accounts.company;
+        let proposal = &ctx.accounts.proposal;
+        let canceller = ctx.accounts.canceller.key();
+
+        let by_owner = if company.signer_threshold == 0 {
+            canceller == company.authority
+        } else {
+            proposal.eta.is_none() && canceller == proposal.proposer
+        };
+        let by_guardian = proposal.eta.is_some() && company.guardians.contains(&canceller);
+        require!(by_owner || by_guardian, ErrorCode::Unauthorized);
+
+        emit!(CompanyActionCancelled {
             company: company.key(),
-            paused: company.paused,
+            proposal: ctx.accounts.proposal.key(),
+            proposal_id: ctx.accounts.proposal.proposal_id,
+            cancelled_by: canceller,
         });
 
         Ok(())
@@ -110,20 +784,111 @@ pub mod prediction_market_rbac {
     // ADMIN ROLE MANAGEMENT (PDA-based)
     // ------------------------------------------------------------------------
 
-    pub fn grant_admin_role(ctx: Context<GrantAdminRole>) -> Result<()> {
-        let company = &mut ctx.accounts.company;
+    /// The company authority grants directly (no `granter_role` needed, which
+    /// is how the first admin is bootstrapped, so do this before enabling
+    /// threshold mode); anyone else must pass their own role PDA.
+    pub fn grant_admin_role(
+        ctx: Context<GrantAdminRole>,
+        permissions: u16,
+        expires_at: Option<i64>,
+    ) -> Result<()> {
+        validate_role_terms(permissions, expires_at, Clock::get()?.unix_timestamp)?;
+
+        require_can_grant(
+            &ctx.accounts.company,
+            &ctx.accounts.granter.key(),
+            ctx.accounts.granter_role.as_deref(),
+            permissions,
+            expires_at,
+        )?;
+
+        // Charge fee to prevent spam
+        anchor_lang::solana_program::program::invoke(
+            &anchor_lang::solana_program::system_instruction::transfer(
+                &ctx.accounts.granter.key(),
+                &ctx.accounts.company.to_account_info().key(),
+                ctx.accounts.platform_config.admin_grant_fee,
+            ),
+            &[
+                ctx.accounts.granter.to_account_info(),
+                ctx.accounts.company.to_account_info(),
+            ],
+        )?;
+
+        apply_admin_role_grant(
+            &mut ctx.accounts.company,
+            &mut ctx.accounts.admin_role,
+            ctx.accounts.recipient.key(),
+            ctx.accounts.granter.key(),
+            permissions,
+            expires_at,
+            ctx.bumps.admin_role,
+        )
+    }
 
+    /// `close_account` returns the role's rent to the company authority and
+    /// frees the PDA so grant_admin_role can be used again later; it also
+    /// closes a role that was revoked earlier. Otherwise the revoked role
+    /// stays on-chain and can be reactivated with regrant_admin_role.
+    /// Threshold-mode companies revoke through a CompanyProposal.
+    pub fn revoke_admin_role(ctx: Context<RevokeAdminRole>, close_account: bool) -> Result<()> {
+        require!(
+            ctx.accounts.company.signer_threshold == 0,
+            ErrorCode::ProposalRequired
+        );
+        require!(
+            has_sensitive_permission(
+                &ctx.accounts.company,
+                &ctx.accounts.revoker.key(),
+                &ctx.accounts.revoker_role,
+                PERM_REVOKE_ROLE
+            ),
+            ErrorCode::MissingPermission
+        );
         require!(
-            company.admin_count < MAX_ADMINS_PER_COMPANY,
-            ErrorCode::TooManyAdmins
+            ctx.accounts.company.timelock_delay == 0,
+            ErrorCode::TimelockRequired
         );
 
+        apply_admin_role_revocation(
+            &mut ctx.accounts.company,
+            &mut ctx.accounts.admin_role,
+            ctx.accounts.revoker.key(),
+            close_account,
+        )?;
+
+        if close_account {
+            ctx.accounts
+                .admin_role
+                .close(ctx.accounts.company_authority.to_account_info())?;
+        }
+
+        Ok(())
+    }
+
+    /// Reactivates a revoked (but not closed) role in place. The previous
+    /// revocation is carried in the event for the audit trail.
+    pub fn regrant_admin_role(
+        ctx: Context<RegrantAdminRole>,
+        permissions: u16,
+        expires_at: Option<i64>,
+    ) -> Result<()> {
+        validate_role_terms(permissions, expires_at, Clock::get()?.unix_timestamp)?;
+        require_can_grant(
+            &ctx.accounts.company,
+            &ctx.accounts.granter.key(),
+            ctx.accounts.granter_role.as_deref(),
+            permissions,
+            expires_at,
+        )?;
+        require!(ctx.accounts.admin_role.revoked, ErrorCode::NotRevoked);
+
         // Charge fee to prevent spam
         anchor_lang::solana_program::program::invoke(
             &anchor_lang::solana_program::system_instruction::transfer(
                 &ctx.accounts.granter.key(),
-                &ctx.accounts.company.to_account_info().key(),
-                ADMIN_GRANT_FEE,
+                &ctx.accounts.company.key(),
+                ctx.accounts.platform_config.admin_grant_fee,
             ),
             &[
                 ctx.accounts.granter.to_account_info(),
@@ -131,47 +896,61 @@ pub mod prediction_market_rbac {
             ],
         )?;
 
+        apply_admin_role_regrant(
+            &mut ctx.accounts.company,
+            &mut ctx.accounts.admin_role,
+            ctx.accounts.granter.key(),
+            permissions,
+            expires_at,
+        )
+    }
+
+    /// Extends (or clears, with None) a role's expiry. Works on roles that
+    /// have already lapsed but have not been swept.
+    pub fn renew_admin_role(ctx: Context<RenewAdminRole>, expires_at: Option<i64>) -> Result<()> {
+        let renewer = ctx.accounts.renewer.key();
+        let company = &ctx.accounts.company;
         let admin_role = &mut ctx.accounts.admin_role;
-        admin_role.user = ctx.accounts.recipient.key();
-        admin_role.company = company.key();
-        admin_role.granted_at = Clock::get()?.unix_timestamp;
-        admin_role.granted_by = ctx.accounts.granter.key();
-        admin_role.revoked = false;
-        admin_role.bump = ctx.bumps.admin_role;
-
-        company.admin_count = company
-            .admin_count
-            .checked_add(1)
-            .ok_or(ErrorCode::Overflow)?;
 
-        emit!(AdminRoleGranted {
-            company: company.key(),
-            user: admin_role.user,
-            granted_by: admin_role.granted_by,
-        });
+        // Only the single-key authority may extend its own role
+        require!(
+            renewer != admin_role.user
+                || (company.signer_threshold == 0 && renewer == company.authority),
+            ErrorCode::SelfRenewal
+        );
 
-        Ok(())
+        // Same rule as granting: admins cannot extend bits they lack, or
+        // past their own expiry
+        require_can_grant(
+            company,
+            &renewer,
+            ctx.accounts.renewer_role.as_deref(),
+            admin_role.permissions,
+            expires_at,
+        )?;
+
+        apply_admin_role_renewal(admin_role, renewer, expires_at)
     }
 
-    pub fn revoke_admin_role(ctx: Context<RevokeAdminRole>) -> Result<()> {
+    /// Permissionless cleanup of a lapsed role. Rent goes back to the
+    /// company authority.
+    pub fn sweep_expired_role(ctx: Context<SweepExpiredRole>) -> Result<()> {
         let company = &mut ctx.accounts.company;
-        let admin_role = &mut ctx.accounts.admin_role;
+        let admin_role = &ctx.accounts.admin_role;
 
-        require!(!admin_role.revoked, ErrorCode::AlreadyRevoked);
+        require!(role_is_expired(admin_role), ErrorCode::RoleNotExpired);
 
-        admin_role.revoked = true;
-        admin_role.revoked_at = Some(Clock::get()?.unix_timestamp);
-        admin_role.revoked_by = Some(ctx.accounts.revoker.key());
-
-        company.admin_count = company
-            .admin_count
-            .checked_sub(1)
-            .ok_or(ErrorCode::Underflow)?;
+        // Revoked roles were already removed from admin_count
+        if !admin_role.revoked {
+            company.admin_count = company
+                .admin_count
+                .checked_sub(1)
+                .ok_or(ErrorCode::Underflow)?;
+        }
 
-        emit!(AdminRoleRevoked {
+        emit!(AdminRoleExpired {
             company: company.key(),
             user: admin_role.user,
-            revoked_by: ctx.accounts.revoker.key(),
         });
 
         Ok(())
@@ -186,23 +965,69 @@ pub mod prediction_market_rbac {
         market_id: u64,
         title: String,
         description: String,
+        close_time: i64,
         resolution_time: i64,
         num_outcomes: u8,
+        resolver: Pubkey,
+        resolver_committee: Vec<Pubkey>,
+        resolution_threshold: u8,
+        oracle: Option<Pubkey>,
+        market_type: MarketType,
+        liquidity_b: u64,
+        early_exit_fee_bps: u16,
+        scalar_lower: i64,
+        scalar_upper: i64,
+        outcome_labels: Vec<String>,
+        metadata_uri: String,
+        metadata_hash: [u8; 32],
     ) -> Result<()> {
         require!(title.len() <= MAX_MARKET_TITLE_LEN, ErrorCode::TitleTooLong);
         require!(
             description.len() <= MAX_MARKET_DESC_LEN,
             ErrorCode::DescriptionTooLong
         );
-        require!(num_outcomes >= 2, ErrorCode::InvalidOutcomes);
         require!(
-            resolution_time > Clock::get()?.unix_timestamp,
+            metadata_uri.len() <= MAX_METADATA_URI_LEN,
+            ErrorCode::MetadataUriTooLong
+        );
+        require!(
+            num_outcomes >= 2 && num_outcomes as usize <= MAX_OUTCOMES,
+            ErrorCode::InvalidOutcomes
+        );
+        require!(
+            close_time > Clock::get()?.unix_timestamp,
+            ErrorCode::InvalidCloseTime
+        );
+        require!(
+            resolution_time >= close_time,
             ErrorCode::InvalidResolutionTime
         );
         require!(
-            !ctx.accounts.company.paused,
-            ErrorCode::CompanyPaused
+            early_exit_fee_bps <= MAX_EARLY_EXIT_FEE_BPS,
+            ErrorCode::FeeTooHigh
         );
+        require!(
+            outcome_labels.len() == num_outcomes as usize,
+            ErrorCode::InvalidOutcomeLabels
+        );
+        require!(
+            outcome_labels
+                .iter()
+                .all(|label| !label.is_empty() && label.len() <= MAX_OUTCOME_LABEL_LEN),
+            ErrorCode::InvalidOutcomeLabels
+        );
+        validate_committee(&resolver_committee, resolution_threshold)?;
+        require!(
+            oracle.is_none() || resolution_threshold == 0,
+            ErrorCode::WrongResolutionMode
+        );
+        if market_type == MarketType::Scalar {
+            // Outcome 0 is Short, 1 is Long; committees vote on discrete outcomes only
+            require!(num_outcomes == 2, ErrorCode::InvalidOutcomes);
+            require!(scalar_lower < scalar_upper, ErrorCode::InvalidScalarRange);
+            require!(resolution_threshold == 0, ErrorCode::WrongResolutionMode);
+        }
+        require_not_paused(&ctx.accounts.company, PAUSE_CREATION)?;
 
         // Rate limiting
         let rate_limit = &mut ctx.accounts.rate_limit_state;
@@ -225,13 +1050,74 @@ pub mod prediction_market_rbac {
         market.creator = ctx.accounts.admin.key();
         market.title = title;
         market.description = description;
+        market.metadata_uri = metadata_uri;
+        market.metadata_hash = metadata_hash;
         market.created_at = now;
+        market.close_time = close_time;
         market.resolution_time = resolution_time;
         market.num_outcomes = num_outcomes;
-        market.is_resolved = false;
+        market.resolver = resolver;
+        market.resolver_committee = resolver_committee;
+        market.resolution_threshold = resolution_threshold;
+        market.vote_counts = [0; MAX_OUTCOMES];
+        market.votes_cast = 0;
+        market.oracle = oracle;
+        market.status = MarketStatus::Draft;
         market.winning_outcome = None;
+        market.scalar_value = None;
+        market.scalar_lower = scalar_lower;
+        market.scalar_upper = scalar_upper;
+        market.proposed_value = None;
+        market.proposer = None;
+        market.challenger = None;
+        market.dispute_deadline = 0;
+        market.bond_amount = 0;
         market.total_volume = 0;
+        market.outcome_stakes = [0; MAX_OUTCOMES];
+        market.claimed_stake = 0;
+        market.total_paid_out = 0;
+        market.cancelled_at = None;
+        market.mint = ctx.accounts.mint.key();
+        market.vault = ctx.accounts.vault.key();
+        market.market_type = market_type;
+        market.early_exit_fee_bps = early_exit_fee_bps;
+        market.fees = fees_for(&ctx.accounts.platform_config, &market.company);
+        market.platform_fees_owed = 0;
+        market.company_fees_owed = 0;
+        market.liquidity_b = 0;
+        market.lmsr_shares = [0; MAX_OUTCOMES];
+        market.implied_probs = [0; MAX_OUTCOMES];
         market.bump = ctx.bumps.market;
+        market.vault_bump = ctx.bumps.vault;
+
+        if market_type == MarketType::Lmsr {
+            require!(liquidity_b > 0, ErrorCode::InvalidLiquidity);
+
+            // Creator funds the market maker's worst-case loss, b * ln(n)
+            let subsidy = lmsr_subsidy(liquidity_b, num_outcomes)?;
+            let funding = ctx
+                .accounts
+                .creator_token_account
+                .as_ref()
+                .ok_or(ErrorCode::FundingAccountRequired)?;
+
+            transfer_to_vault(
+                funding.to_account_info(),
+                &ctx.accounts.vault,
+                ctx.accounts.admin.to_account_info(),
+                &ctx.accounts.token_program,
+                subsidy,
+            )?;
+
+            market.liquidity_b = liquidity_b;
+            market.implied_probs =
+                lmsr_prices(&market.lmsr_shares[..num_outcomes as usize], liquidity_b)?;
+        }
+
+        let outcomes = &mut ctx.accounts.outcomes;
+        outcomes.market = market.key();
+        outcomes.labels = outcome_labels;
+        outcomes.bump = ctx.bumps.outcomes;
 
         let company = &mut ctx.accounts.company;
         company.total_markets = company
@@ -249,512 +1135,4512 @@ pub mod prediction_market_rbac {
         Ok(())
     }
 
-    pub fn resolve_market(
-        ctx: Context<ResolveMarket>,
-        winning_outcome: u8,
+    /// Permissionless: creates the pool shard for one outcome. open_market
+    /// requires every outcome's shard to exist.
+    pub fn init_outcome_ledger(ctx: Context<InitOutcomeLedger>, outcome: u8) -> Result<()> {
+        let market = &ctx.accounts.market;
+
+        require!(market.status == MarketStatus::Draft, ErrorCode::MarketNotDraft);
+        require!(outcome < market.num_outcomes, ErrorCode::InvalidOutcome);
+
+        let ledger = &mut ctx.accounts.ledger;
+        ledger.market = market.key();
+        ledger.outcome = outcome;
+        ledger.stake = 0;
+        ledger.bettors = 0;
+        ledger.volume = 0;
+        ledger.positions_opened = 0;
+        ledger.positions_closed = 0;
+        ledger.basis_in = 0;
+        ledger.basis_out = 0;
+        ledger.bump = ctx.bumps.ledger;
+
+        Ok(())
+    }
+
+    /// Resolution rules are only editable before anyone can bet on them.
+    pub fn update_market_metadata(
+        ctx: Context<UpdateMarketMetadata>,
+        metadata_uri: String,
+        metadata_hash: [u8; 32],
     ) -> Result<()> {
-        let market = &mut ctx.accounts.market;
+        require_not_paused(&ctx.accounts.company, PAUSE_CREATION)?;
 
-        require!(!market.is_resolved, ErrorCode::AlreadyResolved);
-        require!(
-            Clock::get()?.unix_timestamp >= market.resolution_time,
-            ErrorCode::TooEarlyToResolve
-        );
         require!(
-            winning_outcome < market.num_outcomes,
-            ErrorCode::InvalidOutcome
+            metadata_uri.len() <= MAX_METADATA_URI_LEN,
+            ErrorCode::MetadataUriTooLong
         );
 
-        market.is_resolved = true;
-        market.winning_outcome = Some(winning_outcome);
-        market.resolved_at = Some(Clock::get()?.unix_timestamp);
-        market.resolved_by = Some(ctx.accounts.admin.key());
+        let market = &mut ctx.accounts.market;
+        require!(market.status == MarketStatus::Draft, ErrorCode::MarketNotDraft);
+
+        market.metadata_uri = metadata_uri;
+        market.metadata_hash = metadata_hash;
 
-        emit!(MarketResolved {
+        emit!(MarketMetadataUpdated {
             market: market.key(),
-            winning_outcome,
-            resolved_by: ctx.accounts.admin.key(),
+            metadata_uri: market.metadata_uri.clone(),
+            metadata_hash,
+        });
+
+        Ok(())
+    }
+
+    pub fn open_market(ctx: Context<OpenMarket>) -> Result<()> {
+        require_not_paused(&ctx.accounts.company, PAUSE_CREATION)?;
+
+        let market = &mut ctx.accounts.market;
+        let now = Clock::get()?.unix_timestamp;
+
+        require!(now < market.close_time, ErrorCode::InvalidCloseTime);
+        // Settlement reads every shard, so they must all exist before betting
+        load_outcome_ledgers(market, ctx.remaining_accounts)?;
+        transition_market(market, MarketStatus::Open, now)?;
+
+        emit!(MarketOpened {
+            market: market.key(),
+            close_time: market.close_time,
         });
 
         Ok(())
     }
 
     // ------------------------------------------------------------------------
-    // BETTING (Admin or Employee with Merkle Proof)
-    // ------------------------------------------------------------------------
+    // MARKET RESOLUTION (Optimistic: propose -> dispute window -> finalize)
+    // ------------------------------------------------------------------------
 
-    pub fn place_bet(
-        ctx: Context<PlaceBet>,
-        amount: u64,
-        outcome: u8,
-        employee_proof: Option<Vec<[u8; 32]>>,
-        employee_proof_version: Option<u64>,
-    ) -> Result<()> {
-        let market = &ctx.accounts.market;
+    /// `value` is the outcome index for categorical markets and the
+    /// observed value for scalar markets.
+    pub fn propose_outcome(ctx: Context<ProposeOutcome>, value: i64) -> Result<()> {
+        require_not_paused(&ctx.accounts.company, PAUSE_RESOLUTION)?;
+
+        let market = &mut ctx.accounts.market;
+        let company = &ctx.accounts.company;
+        let now = Clock::get()?.unix_timestamp;
 
-        require!(amount > 0, ErrorCode::InvalidAmount);
-        require!(!market.is_resolved, ErrorCode::MarketResolved);
-        require!(outcome < market.num_outcomes, ErrorCode::InvalidOutcome);
         require!(
-            Clock::get()?.unix_timestamp < market.resolution_time,
-            ErrorCode::BettingClosed
+            market.resolution_threshold == 0 && market.oracle.is_none(),
+            ErrorCode::WrongResolutionMode
+        );
+        require!(
+            ctx.accounts.proposer.key() == market.resolver,
+            ErrorCode::NotDesignatedResolver
         );
         require!(
-            !ctx.accounts.company.paused,
-            ErrorCode::CompanyPaused
+            ctx.accounts.proposer_bet.data_is_empty(),
+            ErrorCode::ConflictOfInterest
         );
+        require!(now >= market.resolution_time, ErrorCode::TooEarlyToResolve);
+        validate_resolution_value(market, value)?;
+        transition_market(market, MarketStatus::Proposed, now)?;
+
+        // Proposer bond is returned on finalization, forfeited if overturned
+        let bond = company.resolution_bond;
+        if bond > 0 {
+            transfer_to_vault(
+                ctx.accounts.proposer_token_account.to_account_info(),
+                &ctx.accounts.bond_vault,
+                ctx.accounts.proposer.to_account_info(),
+                &ctx.accounts.token_program,
+                bond,
+            )?;
+        }
 
-        // Check authorization: Admin PDA or Employee Merkle Proof
-        let user_key = ctx.accounts.user.key();
-        let is_admin = ctx.accounts.admin_role.is_some()
-            && !ctx.accounts.admin_role.as_ref().unwrap().revoked;
+        market.proposed_value = Some(value);
+        market.proposer = Some(ctx.accounts.proposer.key());
+        market.dispute_deadline = now
+            .checked_add(company.dispute_window)
+            .ok_or(ErrorCode::Overflow)?;
+        market.bond_amount = bond;
+        market.bond_vault_bump = ctx.bumps.bond_vault;
 
-        if !is_admin {
-            // Must provide valid employee merkle proof
-            let proof = employee_proof.ok_or(ErrorCode::ProofRequired)?;
-            let proof_version = employee_proof_version.ok_or(ErrorCode::ProofVersionRequired)?;
+        emit!(OutcomeProposed {
+            market: market.key(),
+            value,
+            proposer: ctx.accounts.proposer.key(),
+            dispute_deadline: market.dispute_deadline,
+            bond,
+        });
 
-            require!(
-                proof.len() <= MAX_MERKLE_PROOF_DEPTH,
-                ErrorCode::ProofTooDeep
-            );
+        Ok(())
+    }
 
-            require!(
-                proof_version == ctx.accounts.company.employee_root_version,
-                ErrorCode::StaleProof
-            );
+    pub fn dispute_outcome(
+        ctx: Context<DisputeOutcome>,
+        employee_proof: Option<Vec<[u8; 32]>>,
+        employee_proof_version: Option<u64>,
+    ) -> Result<()> {
+        let market = &mut ctx.accounts.market;
+        let now = Clock::get()?.unix_timestamp;
 
-            let leaf = hash_leaf(&user_key);
-            require!(
-                verify_merkle_proof(&proof, ctx.accounts.company.employee_merkle_root, leaf),
-                ErrorCode::NotAuthorized
-            );
+        require!(now < market.dispute_deadline, ErrorCode::DisputeWindowClosed);
+        require_not_paused(&ctx.accounts.company, PAUSE_RESOLUTION)?;
+
+        // Any eligible employee may challenge: Admin PDA or Employee Merkle Proof
+        let challenger = ctx.accounts.challenger.key();
+        verify_bettor(
+            &ctx.accounts.company,
+            &ctx.accounts.admin_role,
+            &challenger,
+            employee_proof,
+            employee_proof_version,
+        )?;
+
+        transition_market(market, MarketStatus::Disputed, now)?;
+
+        // Challenger matches the proposer's bond
+        if market.bond_amount > 0 {
+            transfer_to_vault(
+                ctx.accounts.challenger_token_account.to_account_info(),
+                &ctx.accounts.bond_vault,
+                ctx.accounts.challenger.to_account_info(),
+                &ctx.accounts.token_program,
+                market.bond_amount,
+            )?;
         }
 
-        // Create bet
-        let bet = &mut ctx.accounts.bet;
-        bet.market = market.key();
-        bet.user = user_key;
-        bet.amount = amount;
-        bet.outcome = outcome;
-        bet.placed_at = Clock::get()?.unix_timestamp;
-        bet.claimed = false;
-        bet.bump = ctx.bumps.bet;
-
-        // Update market volume
-        // Note: In production, use separate account to avoid concurrent access issues
-        // ctx.accounts.market.total_volume += amount;
+        market.challenger = Some(challenger);
 
-        emit!(BetPlaced {
-            bet: bet.key(),
+        emit!(OutcomeDisputed {
             market: market.key(),
-            user: user_key,
-            amount,
-            outcome,
+            challenger,
+            bond: market.bond_amount,
         });
 
         Ok(())
     }
 
-    pub fn claim_winnings(ctx: Context<ClaimWinnings>) -> Result<()> {
-        let bet = &mut ctx.accounts.bet;
-        let market = &ctx.accounts.market;
+    /// Permissionless crank: an undisputed proposal becomes final once the
+    /// dispute window has elapsed.
+    pub fn finalize_outcome(ctx: Context<FinalizeOutcome>) -> Result<()> {
+        require_not_paused(&ctx.accounts.company, PAUSE_RESOLUTION)?;
 
-        require!(market.is_resolved, ErrorCode::NotResolved);
-        require!(!bet.claimed, ErrorCode::AlreadyClaimed);
+        let market = &mut ctx.accounts.market;
+        let now = Clock::get()?.unix_timestamp;
+
+        // Disputed proposals can only be settled by arbitrate_outcome
+        require!(market.status == MarketStatus::Proposed, ErrorCode::InvalidStateTransition);
+        require!(now >= market.dispute_deadline, ErrorCode::DisputeWindowOpen);
+
+        let value = market.proposed_value.ok_or(ErrorCode::NotResolved)?;
+        let proposer = market.proposer.ok_or(ErrorCode::NotResolved)?;
+        let totals = load_pool_totals(market, ctx.remaining_accounts)?;
+        settle_market(market, &totals, value, proposer, now)?;
+
+        if market.bond_amount > 0 {
+            transfer_from_vault(
+                market,
+                &ctx.accounts.bond_vault,
+                ctx.accounts.proposer_token_account.to_account_info(),
+                &ctx.accounts.token_program,
+                market.bond_amount,
+            )?;
+        }
 
-        let winning_outcome = market.winning_outcome.ok_or(ErrorCode::NotResolved)?;
-        require!(bet.outcome == winning_outcome, ErrorCode::LosingBet);
+        Ok(())
+    }
 
-        // Calculate payout (simplified - in production use proper AMM formula)
-        let payout = bet.amount.checked_mul(2).ok_or(ErrorCode::Overflow)?;
+    /// The company (single-key authority, or an admin with ResolveMarket
+    /// who is neither party) settles a disputed proposal. `ruling` of None
+    /// voids the market. The side that was wrong forfeits its bond to the other.
+    pub fn arbitrate_outcome(ctx: Context<ArbitrateOutcome>, ruling: Option<i64>) -> Result<()> {
+        require_not_paused(&ctx.accounts.company, PAUSE_RESOLUTION)?;
 
-        // Transfer winnings (in production, pull from pool account)
-        // ... implement token transfer logic
+        let market = &mut ctx.accounts.market;
+        let now = Clock::get()?.unix_timestamp;
+        let arbitrator = ctx.accounts.authority.key();
 
-        bet.claimed = true;
-        bet.claimed_at = Some(Clock::get()?.unix_timestamp);
+        require!(
+            has_sensitive_permission(
+                &ctx.accounts.company,
+                &arbitrator,
+                &ctx.accounts.admin_role,
+                PERM_RESOLVE_MARKET
+            ),
+            ErrorCode::MissingPermission
+        );
+        require!(market.status == MarketStatus::Disputed, ErrorCode::NotDisputed);
+        require!(
+            ctx.accounts.arbitrator_bet.data_is_empty()
+                && market.proposer != Some(arbitrator)
+                && market.challenger != Some(arbitrator),
+            ErrorCode::ConflictOfInterest
+        );
 
-        emit!(WinningsClaimed {
-            bet: bet.key(),
-            user: bet.user,
-            payout,
+        let bond = market.bond_amount;
+        let (proposer_payout, challenger_payout) = match ruling {
+            Some(value) => {
+                validate_resolution_value(market, value)?;
+                let totals = load_pool_totals(market, ctx.remaining_accounts)?;
+                settle_market(market, &totals, value, arbitrator, now)?;
+
+                let total_bonds = bond.checked_mul(2).ok_or(ErrorCode::Overflow)?;
+                if market.proposed_value == Some(value) {
+                    (total_bonds, 0)
+                } else {
+                    (0, total_bonds)
+                }
+            }
+            None => {
+                transition_market(market, MarketStatus::Cancelled, now)?;
+                market.cancelled_at = Some(now);
+                (bond, bond)
+            }
+        };
+
+        if proposer_payout > 0 {
+            transfer_from_vault(
+                market,
+                &ctx.accounts.bond_vault,
+                ctx.accounts.proposer_token_account.to_account_info(),
+                &ctx.accounts.token_program,
+                proposer_payout,
+            )?;
+        }
+        if challenger_payout > 0 {
+            transfer_from_vault(
+                market,
+                &ctx.accounts.bond_vault,
+                ctx.accounts.challenger_token_account.to_account_info(),
+                &ctx.accounts.token_program,
+                challenger_payout,
+            )?;
+        }
+
+        emit!(DisputeArbitrated {
+            market: market.key(),
+            ruling,
+            arbitrator,
         });
 
         Ok(())
     }
-}
 
-// ============================================================================
-// ACCOUNT STRUCTURES
-// ============================================================================
+    // ------------------------------------------------------------------------
+    // MARKET RESOLUTION (Committee: M-of-N resolver votes)
+    // ------------------------------------------------------------------------
 
-#[account]
-pub struct Company {
-    pub authority: Pubkey,              // 32
-    pub company_id: u64,                // 8
-    pub name: String,                   // 4 + 32 = 36
-    pub admin_count: u16,               // 2
-    pub employee_merkle_root: [u8; 32], // 32
-    pub employee_root_version: u64,     // 8
-    pub created_at: i64,                // 8
-    pub paused: bool,                   // 1
-    pub total_markets: u64,             // 8
-    pub bump: u8,                       // 1
-    // Total: 136 bytes (+ 8 discriminator = 144)
-}
+    pub fn submit_resolution_vote(ctx: Context<SubmitResolutionVote>, outcome: u8) -> Result<()> {
+        require_not_paused(&ctx.accounts.company, PAUSE_RESOLUTION)?;
 
-#[account]
-pub struct AdminRole {
-    pub user: Pubkey,             // 32
-    pub company: Pubkey,          // 32
-    pub granted_at: i64,          // 8
-    pub granted_by: Pubkey,       // 32
-    pub revoked: bool,            // 1
-    pub revoked_at: Option<i64>,  // 1 + 8 = 9
-    pub revoked_by: Option<Pubkey>, // 1 + 32 = 33
-    pub bump: u8,                 // 1
-    // Total: 148 bytes (+ 8 discriminator = 156)
-}
+        let market = &mut ctx.accounts.market;
+        let voter = ctx.accounts.resolver.key();
+        let now = Clock::get()?.unix_timestamp;
 
-#[account]
-pub struct Market {
-    pub company: Pubkey,                   // 32
-    pub market_id: u64,                    // 8
-    pub creator: Pubkey,                   // 32
-    pub title: String,                     // 4 + 64 = 68
-    pub description: String,               // 4 + 128 = 132
-    pub created_at: i64,                   // 8
-    pub resolution_time: i64,              // 8
-    pub num_outcomes: u8,                  // 1
-    pub is_resolved: bool,                 // 1
-    pub winning_outcome: Option<u8>,       // 1 + 1 = 2
-    pub resolved_at: Option<i64>,          // 1 + 8 = 9
-    pub resolved_by: Option<Pubkey>,       // 1 + 32 = 33
-    pub total_volume: u64,                 // 8
-    pub bump: u8,                          // 1
-    // Total: 343 bytes (+ 8 discriminator = 351)
-}
+        require!(
+            market.resolution_threshold > 0,
+            ErrorCode::WrongResolutionMode
+        );
+        require!(
+            market.resolver_committee.contains(&voter),
+            ErrorCode::NotDesignatedResolver
+        );
+        require!(
+            ctx.accounts.resolver_bet.data_is_empty(),
+            ErrorCode::ConflictOfInterest
+        );
+        require!(now >= market.resolution_time, ErrorCode::TooEarlyToResolve);
+        require!(
+            market_status(market, now) == MarketStatus::Closed,
+            ErrorCode::InvalidStateTransition
+        );
+        require!(outcome < market.num_outcomes, ErrorCode::InvalidOutcome);
 
-#[account]
-pub struct Bet {
-    pub market: Pubkey,          // 32
-    pub user: Pubkey,            // 32
-    pub amount: u64,             // 8
-    pub outcome: u8,             // 1
-    pub placed_at: i64,          // 8
-    pub claimed: bool,           // 1
-    pub claimed_at: Option<i64>, // 1 + 8 = 9
-    pub bump: u8,                // 1
-    // Total: 92 bytes (+ 8 discriminator = 100)
-}
+        // The vote PDA is `init`, so each resolver votes at most once
+        let vote = &mut ctx.accounts.vote;
+        vote.market = market.key();
+        vote.resolver = voter;
+        vote.outcome = outcome;
+        vote.voted_at = now;
+        vote.bump = ctx.bumps.vote;
 
-#[account]
-pub struct RateLimitState {
-    pub admin: Pubkey,     // 32
-    pub company: Pubkey,   // 32
-    pub window_start: i64, // 8
-    pub actions_count: u16, // 2
-    pub bump: u8,          // 1
-    // Total: 75 bytes (+ 8 discriminator = 83)
-}
+        let i = outcome as usize;
+        market.vote_counts[i] = market.vote_counts[i]
+            .checked_add(1)
+            .ok_or(ErrorCode::Overflow)?;
+        market.votes_cast = market
+            .votes_cast
+            .checked_add(1)
+            .ok_or(ErrorCode::Overflow)?;
 
-// ============================================================================
-// CONTEXT STRUCTURES
-// ============================================================================
+        emit!(ResolutionVoteCast {
+            market: market.key(),
+            resolver: voter,
+            outcome,
+        });
 
-#[derive(Accounts)]
-#[instruction(company_id: u64, name: String, employee_merkle_root: [u8; 32])]
-pub struct RegisterCompany<'info> {
-    #[account(
-        init,
-        payer = authority,
-        space = 8 + 144,
-        seeds = [b"company", company_id.to_le_bytes().as_ref()],
-        bump
-    )]
-    pub company: Account<'info, Company>,
+        if market.vote_counts[i] >= market.resolution_threshold {
+            let totals = load_pool_totals(market, ctx.remaining_accounts)?;
+            return settle_market(market, &totals, outcome as i64, voter, now);
+        }
 
-    #[account(mut)]
-    pub authority: Signer<'info>,
+        // Tie / no quorum: if no outcome can still reach the threshold with
+        // the remaining votes, void the market rather than leave it stuck
+        let remaining = market.resolver_committee.len() as u8 - market.votes_cast;
+        let leading = market.vote_counts.iter().copied().max().unwrap_or(0);
+        if leading + remaining < market.resolution_threshold {
+            transition_market(market, MarketStatus::Cancelled, now)?;
+            market.cancelled_at = Some(now);
+
+            emit!(MarketCancelled {
+                market: market.key(),
+                cancelled_by: voter,
+            });
+        }
 
-    /// CHECK: Platform treasury for registration fees
-    #[account(mut)]
-    pub platform_treasury: UncheckedAccount<'info>,
+        Ok(())
+    }
 
-    pub system_program: Program<'info, System>,
-}
+    /// Permissionless: a committee that has not reached quorum within the
+    /// company's dispute window after resolution_time voids the market.
+    pub fn expire_resolution_vote(ctx: Context<ExpireResolutionVote>) -> Result<()> {
+        require_not_paused(&ctx.accounts.company, PAUSE_RESOLUTION)?;
 
-#[derive(Accounts)]
-pub struct UpdateEmployeeMerkleRoot<'info> {
-    #[account(
-        mut,
-        constraint = company.authority == authority.key() @ ErrorCode::Unauthorized
-    )]
-    pub company: Account<'info, Company>,
+        let market = &mut ctx.accounts.market;
+        let now = Clock::get()?.unix_timestamp;
 
-    pub authority: Signer<'info>,
-}
+        require!(
+            market.resolution_threshold > 0,
+            ErrorCode::WrongResolutionMode
+        );
 
-#[derive(Accounts)]
-pub struct TogglePause<'info> {
-    #[account(
-        mut,
-        constraint = company.authority == authority.key() @ ErrorCode::Unauthorized
-    )]
-    pub company: Account<'info, Company>,
+        let deadline = market
+            .resolution_time
+            .checked_add(ctx.accounts.company.dispute_window)
+            .ok_or(ErrorCode::Overflow)?;
+        require!(now >= deadline, ErrorCode::DisputeWindowOpen);
+        require!(
+            market_status(market, now) == MarketStatus::Closed,
+            ErrorCode::InvalidStateTransition
+        );
 
-    pub authority: Signer<'info>,
-}
+        transition_market(market, MarketStatus::Cancelled, now)?;
+        market.cancelled_at = Some(now);
 
-#[derive(Accounts)]
-pub struct GrantAdminRole<'info> {
-    #[account(mut)]
-    pub company: Account<'info, Company>,
+        emit!(MarketCancelled {
+            market: market.key(),
+            cancelled_by: Pubkey::default(),
+        });
 
-    #[account(
-        init,
-        payer = granter,
-        space = 8 + 156,
-        seeds = [
-            b"admin_role",
-            company.key().as_ref(),
-            recipient.key().as_ref()
-        ],
-        bump
-    )]
-    pub admin_role: Account<'info, AdminRole>,
+        Ok(())
+    }
 
-    /// CHECK: Granter verification
-    #[account(mut)]
-    pub granter: Signer<'info>,
+    // ------------------------------------------------------------------------
+    // MARKET RESOLUTION (Oracle: Ed25519-signed report)
+    // ------------------------------------------------------------------------
 
-    #[account(
-        seeds = [
-            b"admin_role",
-            company.key().as_ref(),
-            granter.key().as_ref()
-        ],
-        bump,
-        constraint = granter_role.key() == granter_role_check.key() || granter.key() == company.authority
-            @ ErrorCode::Unauthorized
-    )]
-    pub granter_role: Account<'info, AdminRole>,
+    /// Permissionless: the transaction must include an Ed25519 program
+    /// instruction, immediately before this one, carrying the oracle's
+    /// signature over `market || value || timestamp` (value and timestamp
+    /// as little-endian i64; value is the outcome index for categorical
+    /// markets).
+    pub fn resolve_with_oracle(
+        ctx: Context<ResolveWithOracle>,
+        value: i64,
+        report_timestamp: i64,
+    ) -> Result<()> {
+        require_not_paused(&ctx.accounts.company, PAUSE_RESOLUTION)?;
 
-    /// CHECK: Additional verification to prevent bypassing
-    #[account(
-        constraint = !granter_role_check.revoked @ ErrorCode::RoleRevoked
-    )]
-    pub granter_role_check: Account<'info, AdminRole>,
+        let market = &mut ctx.accounts.market;
+        let now = Clock::get()?.unix_timestamp;
 
-    /// CHECK: Recipient of admin role
-    pub recipient: AccountInfo<'info>,
+        let oracle = market.oracle.ok_or(ErrorCode::WrongResolutionMode)?;
+        validate_resolution_value(market, value)?;
+        require!(
+            report_timestamp >= market.resolution_time && report_timestamp <= now,
+            ErrorCode::InvalidOracleReport
+        );
 
-    pub system_program: Program<'info, System>,
-}
+        let mut report = Vec::with_capacity(ORACLE_REPORT_LEN);
+        report.extend_from_slice(market.key().as_ref());
+        report.extend_from_slice(&value.to_le_bytes());
+        report.extend_from_slice(&report_timestamp.to_le_bytes());
 
-#[derive(Accounts)]
-pub struct RevokeAdminRole<'info> {
-    #[account(mut)]
-    pub company: Account<'info, Company>,
+        verify_oracle_signature(&ctx.accounts.instructions, &oracle, &report)?;
 
-    #[account(
-        mut,
-        seeds = [
-            b"admin_role",
-            company.key().as_ref(),
-            admin_role.user.as_ref()
-        ],
-        bump = admin_role.bump
-    )]
+        let totals = load_pool_totals(market, ctx.remaining_accounts)?;
+        settle_market(market, &totals, value, oracle, now)
+    }
+
+    /// Permissionless crank: folds the outcome ledgers into Market's
+    /// total_volume and outcome_stakes. Trades only write their outcome's
+    /// shard, so Market's totals are as fresh as the last sync until
+    /// settlement, which records them for good.
+    pub fn sync_market_totals(ctx: Context<SyncMarketTotals>) -> Result<()> {
+        let market = &mut ctx.accounts.market;
+
+        require!(
+            matches!(
+                market.status,
+                MarketStatus::Open | MarketStatus::Proposed | MarketStatus::Disputed
+            ),
+            ErrorCode::InvalidStateTransition
+        );
+
+        let totals = load_pool_totals(market, ctx.remaining_accounts)?;
+        record_pool_totals(market, &totals);
+
+        emit!(MarketTotalsSynced {
+            market: market.key(),
+            total_volume: market.total_volume,
+            outcome_stakes: market.outcome_stakes,
+        });
+
+        Ok(())
+    }
+
+    /// Voiding is as final as a resolution, so like arbitrate_outcome it
+    /// takes the single-key authority or an admin with ResolveMarket, and
+    /// the canceller must hold no position in the market.
+    pub fn cancel_market(ctx: Context<CancelMarket>) -> Result<()> {
+        require_not_paused(&ctx.accounts.company, PAUSE_RESOLUTION)?;
+
+        let authority = ctx.accounts.authority.key();
+        require!(
+            has_sensitive_permission(
+                &ctx.accounts.company,
+                &authority,
+                &ctx.accounts.admin_role,
+                PERM_RESOLVE_MARKET
+            ),
+            ErrorCode::MissingPermission
+        );
+        require!(
+            ctx.accounts.authority_bet.data_is_empty(),
+            ErrorCode::ConflictOfInterest
+        );
+
+        let market = &mut ctx.accounts.market;
+        let now = Clock::get()?.unix_timestamp;
+
+        // Once an outcome is proposed, bonds are escrowed and only finalize
+        // or arbitrate_outcome (which can also void) may settle the market
+        require!(
+            !matches!(market.status, MarketStatus::Proposed | MarketStatus::Disputed),
+            ErrorCode::ResolutionInProgress
+        );
+        transition_market(market, MarketStatus::Cancelled, now)?;
+        market.cancelled_at = Some(now);
+
+        emit!(MarketCancelled {
+            market: market.key(),
+            cancelled_by: authority,
+        });
+
+        Ok(())
+    }
+
+    // ------------------------------------------------------------------------
+    // BETTING (Admin or Employee with Merkle Proof)
+    // ------------------------------------------------------------------------
+
+    pub fn place_bet(
+        ctx: Context<PlaceBet>,
+        amount: u64,
+        outcome: u8,
+        employee_proof: Option<Vec<[u8; 32]>>,
+        employee_proof_version: Option<u64>,
+    ) -> Result<()> {
+        let market = &ctx.accounts.market;
+
+        require!(
+            market.market_type != MarketType::Lmsr,
+            ErrorCode::WrongMarketType
+        );
+        require!(amount > 0, ErrorCode::InvalidAmount);
+        require!(outcome < market.num_outcomes, ErrorCode::InvalidOutcome);
+        require!(
+            market_status(market, Clock::get()?.unix_timestamp) == MarketStatus::Open,
+            ErrorCode::BettingClosed
+        );
+        require_not_paused(&ctx.accounts.company, PAUSE_BETTING)?;
+
+        // Check authorization: Admin PDA or Employee Merkle Proof
+        let user_key = ctx.accounts.user.key();
+        require!(!is_resolver(market, &user_key), ErrorCode::ConflictOfInterest);
+        verify_bettor(
+            &ctx.accounts.company,
+            &ctx.accounts.admin_role,
+            &user_key,
+            employee_proof,
+            employee_proof_version,
+        )?;
+
+        // Escrow stake in the market vault
+        transfer_to_vault(
+            ctx.accounts.user_token_account.to_account_info(),
+            &ctx.accounts.vault,
+            ctx.accounts.user.to_account_info(),
+            &ctx.accounts.token_program,
+            amount,
+        )?;
+
+        // Initialise the account on first bet, otherwise top up / hedge
+        let bet = &mut ctx.accounts.bet;
+        if bet.user == Pubkey::default() {
+            bet.market = market.key();
+            bet.user = user_key;
+            bet.amount = 0;
+            bet.outcome_amounts = [0; MAX_OUTCOMES];
+            bet.placed_at = Clock::get()?.unix_timestamp;
+            bet.claimed = false;
+            bet.bump = ctx.bumps.bet;
+        }
+
+        // Update this outcome's ledger shard so Market stays read-only here
+        // and bets on other outcomes do not contend for the same account
+        let i = outcome as usize;
+        let ledger = &mut ctx.accounts.ledger;
+        open_stake(ledger, bet, i, amount, amount)?;
+        ledger.stake = ledger.stake.checked_add(amount).ok_or(ErrorCode::Overflow)?;
+        ledger.volume = ledger.volume.checked_add(amount).ok_or(ErrorCode::Overflow)?;
+
+        emit!(BetPlaced {
+            bet: bet.key(),
+            market: market.key(),
+            user: user_key,
+            amount,
+            outcome,
+        });
+
+        Ok(())
+    }
+
+    pub fn withdraw_bet(ctx: Context<WithdrawBet>, outcome: u8, amount: u64) -> Result<()> {
+        let market = &ctx.accounts.market;
+        let bet = &mut ctx.accounts.bet;
+
+        require!(
+            market.market_type != MarketType::Lmsr,
+            ErrorCode::WrongMarketType
+        );
+        require!(amount > 0, ErrorCode::InvalidAmount);
+        require!(outcome < market.num_outcomes, ErrorCode::InvalidOutcome);
+        require!(
+            market_status(market, Clock::get()?.unix_timestamp) == MarketStatus::Open,
+            ErrorCode::BettingClosed
+        );
+        require_not_paused(&ctx.accounts.company, PAUSE_BETTING)?;
+
+        let i = outcome as usize;
+        require!(
+            bet.outcome_amounts[i] >= amount,
+            ErrorCode::InsufficientPosition
+        );
+
+        let fee = (amount as u128 * market.early_exit_fee_bps as u128
+            / BPS_DENOMINATOR as u128) as u64;
+        let refund = amount - fee;
+
+        transfer_from_vault(
+            market,
+            &ctx.accounts.vault,
+            ctx.accounts.user_token_account.to_account_info(),
+            &ctx.accounts.token_program,
+            refund,
+        )?;
+
+        // The fee stays in the pool (total_volume) but not in any outcome
+        // stake, so it is paid out pro-rata to the eventual winners
+        let ledger = &mut ctx.accounts.ledger;
+        close_stake(ledger, bet, i, amount, amount)?;
+        ledger.stake = ledger.stake.checked_sub(amount).ok_or(ErrorCode::Underflow)?;
+        ledger.volume = ledger.volume.checked_sub(refund).ok_or(ErrorCode::Underflow)?;
+
+        emit!(BetWithdrawn {
+            bet: bet.key(),
+            market: market.key(),
+            user: bet.user,
+            outcome,
+            amount,
+            fee,
+        });
+
+        Ok(())
+    }
+
+    // ------------------------------------------------------------------------
+    // AMM TRADING (LMSR markets)
+    // ------------------------------------------------------------------------
+
+    pub fn buy_shares(
+        ctx: Context<BuyShares>,
+        outcome: u8,
+        shares: u64,
+        max_cost: u64,
+        employee_proof: Option<Vec<[u8; 32]>>,
+        employee_proof_version: Option<u64>,
+    ) -> Result<()> {
+        let market = &mut ctx.accounts.market;
+
+        require!(
+            market.market_type == MarketType::Lmsr,
+            ErrorCode::WrongMarketType
+        );
+        require!(shares > 0, ErrorCode::InvalidAmount);
+        require!(outcome < market.num_outcomes, ErrorCode::InvalidOutcome);
+        require!(
+            market_status(market, Clock::get()?.unix_timestamp) == MarketStatus::Open,
+            ErrorCode::BettingClosed
+        );
+        require_not_paused(&ctx.accounts.company, PAUSE_BETTING)?;
+
+        let user_key = ctx.accounts.user.key();
+        require!(!is_resolver(market, &user_key), ErrorCode::ConflictOfInterest);
+        verify_bettor(
+            &ctx.accounts.company,
+            &ctx.accounts.admin_role,
+            &user_key,
+            employee_proof,
+            employee_proof_version,
+        )?;
+
+        let n = market.num_outcomes as usize;
+        let i = outcome as usize;
+        let b = market.liquidity_b;
+        let cost = lmsr_buy(&mut market.lmsr_shares[..n], b, i, shares)?;
+
+        require!(cost <= max_cost, ErrorCode::SlippageExceeded);
+
+        transfer_to_vault(
+            ctx.accounts.user_token_account.to_account_info(),
+            &ctx.accounts.vault,
+            ctx.accounts.user.to_account_info(),
+            &ctx.accounts.token_program,
+            cost,
+        )?;
+
+        market.implied_probs = lmsr_prices(&market.lmsr_shares[..n], market.liquidity_b)?;
+
+        // Shares are held on the bettor's position; amount is net cost basis
+        let bet = &mut ctx.accounts.bet;
+        if bet.user == Pubkey::default() {
+            bet.market = market.key();
+            bet.user = user_key;
+            bet.amount = 0;
+            bet.outcome_amounts = [0; MAX_OUTCOMES];
+            bet.placed_at = Clock::get()?.unix_timestamp;
+            bet.claimed = false;
+            bet.bump = ctx.bumps.bet;
+        }
+
+        let ledger = &mut ctx.accounts.ledger;
+        open_stake(ledger, bet, i, shares, cost)?;
+        ledger.volume = ledger.volume.checked_add(cost).ok_or(ErrorCode::Overflow)?;
+
+        emit!(SharesTraded {
+            market: market.key(),
+            user: user_key,
+            outcome,
+            shares,
+            amount: cost,
+            is_buy: true,
+            implied_probs: market.implied_probs,
+        });
+
+        Ok(())
+    }
+
+    pub fn sell_shares(
+        ctx: Context<SellShares>,
+        outcome: u8,
+        shares: u64,
+        min_proceeds: u64,
+    ) -> Result<()> {
+        let market = &mut ctx.accounts.market;
+        let bet = &mut ctx.accounts.bet;
+
+        require!(
+            market.market_type == MarketType::Lmsr,
+            ErrorCode::WrongMarketType
+        );
+        require!(shares > 0, ErrorCode::InvalidAmount);
+        require!(outcome < market.num_outcomes, ErrorCode::InvalidOutcome);
+        require!(
+            market_status(market, Clock::get()?.unix_timestamp) == MarketStatus::Open,
+            ErrorCode::BettingClosed
+        );
+        require_not_paused(&ctx.accounts.company, PAUSE_BETTING)?;
+
+        let i = outcome as usize;
+        require!(
+            bet.outcome_amounts[i] >= shares,
+            ErrorCode::InsufficientPosition
+        );
+
+        let n = market.num_outcomes as usize;
+        let b = market.liquidity_b;
+        let proceeds = lmsr_sell(&mut market.lmsr_shares[..n], b, i, shares)?;
+
+        require!(proceeds >= min_proceeds, ErrorCode::SlippageExceeded);
+
+        transfer_from_vault(
+            market,
+            &ctx.accounts.vault,
+            ctx.accounts.user_token_account.to_account_info(),
+            &ctx.accounts.token_program,
+            proceeds,
+        )?;
+
+        market.implied_probs = lmsr_prices(&market.lmsr_shares[..n], market.liquidity_b)?;
+
+        // Selling out of every outcome releases any remaining basis too
+        let basis_released = if bet.outcome_amounts[i] == shares
+            && bet.outcome_amounts.iter().filter(|&&units| units > 0).count() == 1
+        {
+            bet.amount
+        } else {
+            bet.amount.min(proceeds)
+        };
+        close_stake(&mut ctx.accounts.ledger, bet, i, shares, basis_released)?;
+
+        emit!(SharesTraded {
+            market: market.key(),
+            user: bet.user,
+            outcome,
+            shares,
+            amount: proceeds,
+            is_buy: false,
+            implied_probs: market.implied_probs,
+        });
+
+        Ok(())
+    }
+
+    // ------------------------------------------------------------------------
+    // SETTLEMENT
+    // ------------------------------------------------------------------------
+
+    pub fn claim_winnings(ctx: Context<ClaimWinnings>) -> Result<()> {
+        require_not_paused(&ctx.accounts.company, PAUSE_CLAIMS)?;
+
+        let bet = &mut ctx.accounts.bet;
+        let market = &mut ctx.accounts.market;
+
+        require!(market.status == MarketStatus::Resolved, ErrorCode::NotResolved);
+        require!(!bet.claimed, ErrorCode::AlreadyClaimed);
+
+        let (payout, winning_stake) = position_payout(market, bet)?;
+        require!(winning_stake > 0, ErrorCode::LosingBet);
+
+        // Settlement fee stays in the vault until claimed by each treasury
+        let (platform_fee, company_fee) = settlement_fees(&market.fees, payout);
+        let fee = platform_fee + company_fee;
+        let net_payout = payout - fee;
+
+        transfer_from_vault(
+            market,
+            &ctx.accounts.vault,
+            ctx.accounts.user_token_account.to_account_info(),
+            &ctx.accounts.token_program,
+            net_payout,
+        )?;
+
+        market.claimed_stake = market
+            .claimed_stake
+            .checked_add(winning_stake)
+            .ok_or(ErrorCode::Overflow)?;
+        market.total_paid_out = market
+            .total_paid_out
+            .checked_add(payout)
+            .ok_or(ErrorCode::Overflow)?;
+        market.platform_fees_owed = market
+            .platform_fees_owed
+            .checked_add(platform_fee)
+            .ok_or(ErrorCode::Overflow)?;
+        market.company_fees_owed = market
+            .company_fees_owed
+            .checked_add(company_fee)
+            .ok_or(ErrorCode::Overflow)?;
+
+        bet.claimed = true;
+        bet.claimed_at = Some(Clock::get()?.unix_timestamp);
+
+        emit!(WinningsClaimed {
+            bet: bet.key(),
+            user: bet.user,
+            payout: net_payout,
+            fee,
+        });
+
+        Ok(())
+    }
+
+    pub fn refund_bet(ctx: Context<RefundBet>) -> Result<()> {
+        require_not_paused(&ctx.accounts.company, PAUSE_CLAIMS)?;
+
+        let bet = &mut ctx.accounts.bet;
+        let market = &mut ctx.accounts.market;
+
+        require!(market.status == MarketStatus::Cancelled, ErrorCode::NotCancelled);
+        require!(!bet.claimed, ErrorCode::AlreadyClaimed);
+
+        // Full stake (net cost basis for LMSR), no fees
+        let refund = bet.amount;
+        if refund > 0 {
+            transfer_from_vault(
+                market,
+                &ctx.accounts.vault,
+                ctx.accounts.user_token_account.to_account_info(),
+                &ctx.accounts.token_program,
+                refund,
+            )?;
+        }
+
+        // A cancelled market has no winners, so total_paid_out counts refunds
+        market.total_paid_out = market
+            .total_paid_out
+            .checked_add(refund)
+            .ok_or(ErrorCode::Overflow)?;
+
+        bet.claimed = true;
+        bet.claimed_at = Some(Clock::get()?.unix_timestamp);
+
+        emit!(BetRefunded {
+            bet: bet.key(),
+            user: bet.user,
+            amount: refund,
+        });
+
+        Ok(())
+    }
+
+    pub fn sweep_dust(ctx: Context<SweepDust>) -> Result<()> {
+        require_not_paused(&ctx.accounts.company, PAUSE_CLAIMS)?;
+
+        require!(
+            has_sensitive_permission(
+                &ctx.accounts.company,
+                &ctx.accounts.authority.key(),
+                &ctx.accounts.admin_role,
+                PERM_MANAGE_TREASURY
+            ),
+            ErrorCode::MissingPermission
+        );
+
+        let market = &mut ctx.accounts.market;
+        let now = Clock::get()?.unix_timestamp;
+
+        match market.status {
+            MarketStatus::Cancelled => {
+                // Only once every position has been refunded
+                let totals = load_pool_totals(market, ctx.remaining_accounts)?;
+                require!(
+                    market.total_paid_out == totals.open_interest,
+                    ErrorCode::UnclaimedWinnings
+                );
+            }
+            MarketStatus::Resolved => {
+                // Only once every winning stake has been paid out
+                require!(
+                    market.claimed_stake == winning_stake_total(market)?,
+                    ErrorCode::UnclaimedWinnings
+                );
+            }
+            _ => return err!(ErrorCode::NotResolved),
+        }
+
+        // Unclaimed fees stay behind for claim_platform_fees/claim_company_fees
+        let fees_owed = market
+            .platform_fees_owed
+            .checked_add(market.company_fees_owed)
+            .ok_or(ErrorCode::Overflow)?;
+        let amount = ctx
+            .accounts
+            .vault
+            .amount
+            .checked_sub(fees_owed)
+            .ok_or(ErrorCode::Underflow)?;
+
+        // An LMSR market's leftover is the unspent subsidy plus the market
+        // maker's trading result, so it goes back to the creator who funded it
+        let destination = match market.market_type {
+            MarketType::Lmsr => ctx
+                .accounts
+                .creator_token_account
+                .as_ref()
+                .ok_or(ErrorCode::FundingAccountRequired)?
+                .to_account_info(),
+            _ => ctx.accounts.company_treasury.to_account_info(),
+        };
+        if amount > 0 {
+            transfer_from_vault(
+                market,
+                &ctx.accounts.vault,
+                destination,
+                &ctx.accounts.token_program,
+                amount,
+            )?;
+        }
+
+        // Only owed fees remain: nothing else can happen to this market
+        transition_market(market, MarketStatus::Finalized, now)?;
+
+        emit!(DustSwept {
+            market: market.key(),
+            amount,
+        });
+
+        Ok(())
+    }
+
+    // ------------------------------------------------------------------------
+    // FEES
+    // ------------------------------------------------------------------------
+
+    /// Permissionless crank: the destination is pinned to the canonical
+    /// platform treasury.
+    pub fn claim_platform_fees(ctx: Context<ClaimPlatformFees>) -> Result<()> {
+        let market = &mut ctx.accounts.market;
+        let amount = market.platform_fees_owed;
+        require!(amount > 0, ErrorCode::NoFeesOwed);
+
+        transfer_from_vault(
+            market,
+            &ctx.accounts.vault,
+            ctx.accounts.treasury_token_account.to_account_info(),
+            &ctx.accounts.token_program,
+            amount,
+        )?;
+        market.platform_fees_owed = 0;
+
+        emit!(PlatformFeesClaimed {
+            market: market.key(),
+            amount,
+        });
+
+        Ok(())
+    }
+
+    /// Permissionless crank into the company treasury PDA (one token
+    /// account per company and mint, owned by the company PDA).
+    pub fn claim_company_fees(ctx: Context<ClaimCompanyFees>) -> Result<()> {
+        let market = &mut ctx.accounts.market;
+        let amount = market.company_fees_owed;
+        require!(amount > 0, ErrorCode::NoFeesOwed);
+
+        transfer_from_vault(
+            market,
+            &ctx.accounts.vault,
+            ctx.accounts.company_treasury.to_account_info(),
+            &ctx.accounts.token_program,
+            amount,
+        )?;
+        market.company_fees_owed = 0;
+
+        emit!(CompanyFeesClaimed {
+            company: ctx.accounts.company.key(),
+            market: market.key(),
+            amount,
+        });
+
+        Ok(())
+    }
+
+    pub fn withdraw_company_treasury(
+        ctx: Context<WithdrawCompanyTreasury>,
+        amount: u64,
+    ) -> Result<()> {
+        require!(
+            has_sensitive_permission(
+                &ctx.accounts.company,
+                &ctx.accounts.authority.key(),
+                &ctx.accounts.admin_role,
+                PERM_MANAGE_TREASURY
+            ),
+            ErrorCode::MissingPermission
+        );
+
+        // The company PDA is the treasury authority
+        let company = &ctx.accounts.company;
+        let company_id_bytes = company.company_id.to_le_bytes();
+        let company_seeds: &[&[u8]] = &[b"company", company_id_bytes.as_ref(), &[company.bump]];
+
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.company_treasury.to_account_info(),
+                    to: ctx.accounts.destination.to_account_info(),
+                    authority: company.to_account_info(),
+                },
+                &[company_seeds],
+            ),
+            amount,
+        )?;
+
+        emit!(CompanyTreasuryWithdrawn {
+            company: company.key(),
+            mint: ctx.accounts.company_treasury.mint,
+            amount,
+            destination: ctx.accounts.destination.key(),
+            withdrawn_by: ctx.accounts.authority.key(),
+        });
+
+        Ok(())
+    }
+}
+
+// ============================================================================
+// ACCOUNT STRUCTURES
+// ============================================================================
+
+#[account]
+pub struct PlatformConfig {
+    pub admin: Pubkey,                          // 32
+    pub pending_admin: Option<Pubkey>,          // 1 + 32 = 33
+    pub treasury: Pubkey,                       // 32
+    pub company_registration_fee: u64,          // 8 (lamports)
+    pub admin_grant_fee: u64,                   // 8 (lamports)
+    pub default_fees: FeeSplit,                 // 4
+    pub fee_overrides: Vec<CompanyFeeOverride>, // 4 + 36 * 16 = 580 (MAX_FEE_OVERRIDES)
+    pub paused: bool,                           // 1
+    pub bump: u8,                               // 1
+    // Total: 699 bytes (+ 8 discriminator = 707)
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub struct FeeSplit {
+    pub settlement_fee_bps: u16, // Taken from winning payouts at claim
+    pub platform_share_bps: u16, // Platform's cut of that fee; the rest goes to the company
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub struct CompanyFeeOverride {
+    pub company: Pubkey,
+    pub fees: FeeSplit,
+}
+
+#[account]
+pub struct Company {
+    pub authority: Pubkey,                 // 32
+    pub company_id: u64,                   // 8
+    pub name: String,                      // 4 + 32 = 36
+    pub admin_count: u16,                  // 2
+    pub employee_merkle_root: [u8; 32],    // 32
+    pub employee_root_version: u64,        // 8
+    pub created_at: i64,                   // 8
+    pub pause_flags: u8,                   // 1 (PAUSE_* bitmask)
+    pub total_markets: u64,                // 8
+    pub dispute_window: i64,               // 8
+    pub resolution_bond: u64,              // 8 (market mint base units)
+    pub pending_authority: Option<Pubkey>, // 1 + 32 = 33
+    pub signers: Vec<Pubkey>,              // 4 + 32 * 5 = 164 (MAX_COMPANY_SIGNERS)
+    pub signer_threshold: u8,              // 1 (0 = single authority key)
+    pub signer_set_version: u64,           // 8
+    pub proposal_count: u64,               // 8
+    pub timelock_delay: i64,               // 8 (0 = no timelock)
+    pub guardians: Vec<Pubkey>,            // 4 + 32 * 3 = 100 (MAX_GUARDIANS; pause + cancel)
+    pub pause_guardians: Vec<Pubkey>,      // 4 + 32 * 3 = 100 (MAX_GUARDIANS; pause only)
+    pub bump: u8,                          // 1
+    // Total: 574 bytes (+ 8 discriminator = 582)
+}
+
+#[account]
+pub struct AdminRole {
+    pub user: Pubkey,             // 32
+    pub company: Pubkey,          // 32
+    pub granted_at: i64,          // 8
+    pub granted_by: Pubkey,       // 32
+    pub permissions: u16,         // 2 (PERM_* bitmask)
+    pub expires_at: Option<i64>,  // 1 + 8 = 9
+    pub revoked: bool,            // 1
+    pub revoked_at: Option<i64>,  // 1 + 8 = 9
+    pub revoked_by: Option<Pubkey>, // 1 + 32 = 33
+    pub bump: u8,                 // 1
+    // Total: 159 bytes (+ 8 discriminator = 167)
+}
+
+#[account]
+pub struct Market {
+    pub company: Pubkey,                   // 32
+    pub market_id: u64,                    // 8
+    pub creator: Pubkey,                   // 32
+    pub title: String,                     // 4 + 64 = 68
+    pub description: String,               // 4 + 128 = 132
+    pub metadata_uri: String,              // 4 + 200 = 204
+    pub metadata_hash: [u8; 32],           // 32 (hash of canonical rules JSON)
+    pub created_at: i64,                   // 8
+    pub close_time: i64,                   // 8
+    pub resolution_time: i64,              // 8
+    pub num_outcomes: u8,                  // 1
+    pub resolver: Pubkey,                  // 32 (only key allowed to propose)
+    pub resolver_committee: Vec<Pubkey>,   // 4 + 32 * 5 = 164
+    pub resolution_threshold: u8,          // 1 (0 = single resolver mode)
+    pub vote_counts: [u8; MAX_OUTCOMES],   // 8
+    pub votes_cast: u8,                    // 1
+    pub oracle: Option<Pubkey>,            // 1 + 32 = 33
+    pub status: MarketStatus,              // 1
+    pub winning_outcome: Option<u8>,       // 1 + 1 = 2 (categorical)
+    pub scalar_value: Option<i64>,         // 1 + 8 = 9 (scalar)
+    pub scalar_lower: i64,                 // 8
+    pub scalar_upper: i64,                 // 8
+    pub resolved_at: Option<i64>,          // 1 + 8 = 9
+    pub resolved_by: Option<Pubkey>,       // 1 + 32 = 33
+    pub proposed_value: Option<i64>,       // 1 + 8 = 9
+    pub proposer: Option<Pubkey>,          // 1 + 32 = 33
+    pub challenger: Option<Pubkey>,        // 1 + 32 = 33
+    pub dispute_deadline: i64,             // 8
+    pub bond_amount: u64,                  // 8
+    pub total_volume: u64,                 // 8 (summed from OutcomeLedger by sync_market_totals; final at settlement)
+    pub outcome_stakes: [u64; MAX_OUTCOMES], // 8 * 8 = 64 (as above)
+    pub claimed_stake: u64,                // 8
+    pub total_paid_out: u64,               // 8
+    pub cancelled_at: Option<i64>,         // 1 + 8 = 9
+    pub mint: Pubkey,                      // 32
+    pub vault: Pubkey,                     // 32
+    pub market_type: MarketType,           // 1
+    pub early_exit_fee_bps: u16,           // 2 (parimutuel only)
+    pub fees: FeeSplit,                    // 4 (snapshot of PlatformConfig at creation)
+    pub platform_fees_owed: u64,           // 8
+    pub company_fees_owed: u64,            // 8
+    pub liquidity_b: u64,                  // 8 (LMSR only)
+    pub lmsr_shares: [u64; MAX_OUTCOMES],  // 8 * 8 = 64
+    pub implied_probs: [u64; MAX_OUTCOMES], // 8 * 8 = 64 (PRICE_SCALE)
+    pub bump: u8,                          // 1
+    pub vault_bump: u8,                    // 1
+    pub bond_vault_bump: u8,               // 1
+    // Total: 1256 bytes (+ 8 discriminator = 1264)
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum MarketStatus {
+    Draft,     // Created, not yet accepting bets
+    Open,      // Accepting bets until close_time
+    Closed,    // Past close_time, awaiting resolution (derived from Open)
+    Proposed,  // Outcome proposed, dispute window running
+    Disputed,  // Proposal challenged, awaiting arbitration
+    Resolved,  // Outcome final, claims open
+    Cancelled, // Voided, refunds open
+    Finalized, // All funds settled, vault swept (owed fees aside)
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum MarketType {
+    Parimutuel,
+    Lmsr,
+    Scalar, // Parimutuel Short/Long pools split linearly across [lower, upper]
+}
+
+#[account]
+pub struct CompanyProposal {
+    pub company: Pubkey,            // 32
+    pub proposal_id: u64,           // 8
+    pub proposer: Pubkey,           // 32
+    pub action: CompanyAction,      // 1 + 165 = 166 (UpdateSignerSet is largest)
+    pub approvals: u8,              // 1 (bitmask over Company.signers indices)
+    pub approval_count: u8,         // 1
+    pub signer_set_version: u64,    // 8
+    pub created_at: i64,            // 8
+    pub eta: Option<i64>,           // 1 + 8 = 9 (set once a timelocked action is approved)
+    pub bump: u8,                   // 1
+    // Total: 266 bytes (+ 8 discriminator = 274)
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
+pub enum CompanyAction {
+    UpdateEmployeeMerkleRoot { new_root: [u8; 32] },
+    Unpause { flags: u8, reason: u16 },
+    RevokeAdminRole { user: Pubkey, close_account: bool },
+    SetPendingAuthority { pending_authority: Option<Pubkey> }, // None cancels
+    UpdateSignerSet { signers: Vec<Pubkey>, threshold: u8 },   // Empty with 0 reverts to single key
+    UpdateTimelockConfig { delay: i64, guardians: Vec<Pubkey> },
+    UpdateResolutionConfig { dispute_window: i64, resolution_bond: u64 },
+    GrantAdminRole { user: Pubkey, permissions: u16, expires_at: Option<i64> }, // execute_grant_proposal
+    RegrantAdminRole { user: Pubkey, permissions: u16, expires_at: Option<i64> },
+    RenewAdminRole { user: Pubkey, expires_at: Option<i64> },
+    SetPauseGuardians { guardians: Vec<Pubkey> },
+}
+
+/// One pool shard per outcome, so bets on different outcomes of the same
+/// market write different accounts. Market-wide counters are kept as
+/// monotonic in/out pairs on whichever shard was traded; they only add up
+/// across every shard of the market (see pool_totals).
+#[account]
+pub struct OutcomeLedger {
+    pub market: Pubkey,        // 32
+    pub outcome: u8,           // 1
+    pub stake: u64,            // 8 (parimutuel/scalar; LMSR shares live on Market)
+    pub bettors: u32,          // 4 (positions holding this outcome)
+    pub volume: u64,           // 8 (net pool inflow through this outcome)
+    pub positions_opened: u64, // 8
+    pub positions_closed: u64, // 8
+    pub basis_in: u64,         // 8
+    pub basis_out: u64,        // 8
+    pub bump: u8,              // 1
+    // Total: 86 bytes (+ 8 discriminator = 94)
+}
+
+/// Authoritative outcome names, indexed like Bet.outcome_amounts. Kept out
+/// of Market so the hot account stays small.
+#[account]
+pub struct MarketOutcomes {
+    pub market: Pubkey,      // 32
+    pub labels: Vec<String>, // 4 + 8 * (4 + 32) = 292
+    pub bump: u8,            // 1
+    // Total: 325 bytes (+ 8 discriminator = 333)
+}
+
+#[account]
+pub struct ResolutionVote {
+    pub market: Pubkey,   // 32
+    pub resolver: Pubkey, // 32
+    pub outcome: u8,      // 1
+    pub voted_at: i64,    // 8
+    pub bump: u8,         // 1
+    // Total: 74 bytes (+ 8 discriminator = 82)
+}
+
+#[account]
+pub struct Bet {
+    pub market: Pubkey,          // 32
+    pub user: Pubkey,            // 32
+    pub amount: u64,             // 8 (sum of outcome_amounts)
+    pub outcome_amounts: [u64; MAX_OUTCOMES], // 8 * 8 = 64
+    pub placed_at: i64,          // 8
+    pub claimed: bool,           // 1
+    pub claimed_at: Option<i64>, // 1 + 8 = 9
+    pub bump: u8,                // 1
+    // Total: 155 bytes (+ 8 discriminator = 163)
+}
+
+#[account]
+pub struct RateLimitState {
+    pub admin: Pubkey,     // 32
+    pub company: Pubkey,   // 32
+    pub window_start: i64, // 8
+    pub actions_count: u16, // 2
+    pub bump: u8,          // 1
+    // Total: 75 bytes (+ 8 discriminator = 83)
+}
+
+// ============================================================================
+// CONTEXT STRUCTURES
+// ============================================================================
+
+#[derive(Accounts)]
+pub struct InitializePlatform<'info> {
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + 707,
+        seeds = [b"platform_config"],
+        bump
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+
+    #[account(
+        constraint = program.programdata_address()? == Some(program_data.key())
+            @ ErrorCode::Unauthorized
+    )]
+    pub program: Program<'info, crate::program::PredictionMarketRbac>,
+
+    #[account(
+        constraint = program_data.upgrade_authority_address == Some(admin.key())
+            @ ErrorCode::Unauthorized
+    )]
+    pub program_data: Account<'info, ProgramData>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+// Shared by every admin-only platform instruction. Deliberately ignores
+// the pause flag so the admin can always unpause.
+#[derive(Accounts)]
+pub struct UpdatePlatformConfig<'info> {
+    #[account(
+        mut,
+        seeds = [b"platform_config"],
+        bump = platform_config.bump,
+        has_one = admin @ ErrorCode::Unauthorized
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptPlatformAdmin<'info> {
+    #[account(
+        mut,
+        seeds = [b"platform_config"],
+        bump = platform_config.bump,
+        constraint = platform_config.pending_admin == Some(new_admin.key())
+            @ ErrorCode::NotPendingAuthority
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+
+    pub new_admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(company_id: u64, name: String, employee_merkle_root: [u8; 32])]
+pub struct RegisterCompany<'info> {
+    #[account(
+        seeds = [b"platform_config"],
+        bump = platform_config.bump,
+        constraint = !platform_config.paused @ ErrorCode::PlatformPaused
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + 582,
+        seeds = [b"company", company_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub company: Account<'info, Company>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        address = platform_config.treasury @ ErrorCode::WrongTreasury
+    )]
+    pub platform_treasury: SystemAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateEmployeeMerkleRoot<'info> {
+    #[account(
+        seeds = [b"platform_config"],
+        bump = platform_config.bump,
+        constraint = !platform_config.paused @ ErrorCode::PlatformPaused
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+
+    #[account(mut)]
+    pub company: Account<'info, Company>,
+
+    // Optional: admins with UpdateRoot, otherwise must be company.authority
+    #[account(
+        seeds = [
+            b"admin_role",
+            company.key().as_ref(),
+            authority.key().as_ref()
+        ],
+        bump
+    )]
+    pub admin_role: Option<Account<'info, AdminRole>>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct PauseCompany<'info> {
+    #[account(
+        seeds = [b"platform_config"],
+        bump = platform_config.bump,
+        constraint = !platform_config.paused @ ErrorCode::PlatformPaused
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+
+    #[account(mut)]
+    pub company: Account<'info, Company>,
+
+    // Optional: admins with Pause; either guardian set and company.authority need none
+    #[account(
+        seeds = [
+            b"admin_role",
+            company.key().as_ref(),
+            pauser.key().as_ref()
+        ],
+        bump
+    )]
+    pub admin_role: Option<Account<'info, AdminRole>>,
+
+    pub pauser: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct UnpauseCompany<'info> {
+    #[account(
+        seeds = [b"platform_config"],
+        bump = platform_config.bump,
+        constraint = !platform_config.paused @ ErrorCode::PlatformPaused
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+
+    #[account(mut)]
+    pub company: Account<'info, Company>,
+
+    // Optional: admins with Pause, otherwise must be company.authority
+    #[account(
+        seeds = [
+            b"admin_role",
+            company.key().as_ref(),
+            unpauser.key().as_ref()
+        ],
+        bump
+    )]
+    pub admin_role: Option<Account<'info, AdminRole>>,
+
+    pub unpauser: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetPauseGuardians<'info> {
+    #[account(
+        seeds = [b"platform_config"],
+        bump = platform_config.bump,
+        constraint = !platform_config.paused @ ErrorCode::PlatformPaused
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+
+    #[account(
+        mut,
+        constraint = company.authority == authority.key() @ ErrorCode::Unauthorized
+    )]
+    pub company: Account<'info, Company>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateResolutionConfig<'info> {
+    #[account(
+        seeds = [b"platform_config"],
+        bump = platform_config.bump,
+        constraint = !platform_config.paused @ ErrorCode::PlatformPaused
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+
+    #[account(
+        mut,
+        constraint = company.authority == authority.key() @ ErrorCode::Unauthorized
+    )]
+    pub company: Account<'info, Company>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ProposeAuthorityTransfer<'info> {
+    #[account(
+        seeds = [b"platform_config"],
+        bump = platform_config.bump,
+        constraint = !platform_config.paused @ ErrorCode::PlatformPaused
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+
+    #[account(
+        mut,
+        constraint = company.authority == authority.key() @ ErrorCode::Unauthorized
+    )]
+    pub company: Account<'info, Company>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptAuthorityTransfer<'info> {
+    #[account(
+        seeds = [b"platform_config"],
+        bump = platform_config.bump,
+        constraint = !platform_config.paused @ ErrorCode::PlatformPaused
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+
+    #[account(
+        mut,
+        constraint = company.pending_authority == Some(new_authority.key())
+            @ ErrorCode::NotPendingAuthority
+    )]
+    pub company: Account<'info, Company>,
+
+    pub new_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CancelAuthorityTransfer<'info> {
+    #[account(
+        seeds = [b"platform_config"],
+        bump = platform_config.bump,
+        constraint = !platform_config.paused @ ErrorCode::PlatformPaused
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+
+    #[account(
+        mut,
+        constraint = company.authority == authority.key() @ ErrorCode::Unauthorized
+    )]
+    pub company: Account<'info, Company>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ConfigureSignerSet<'info> {
+    #[account(
+        seeds = [b"platform_config"],
+        bump = platform_config.bump,
+        constraint = !platform_config.paused @ ErrorCode::PlatformPaused
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+
+    #[account(
+        mut,
+        constraint = company.authority == authority.key() @ ErrorCode::Unauthorized
+    )]
+    pub company: Account<'info, Company>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CreateCompanyProposal<'info> {
+    #[account(
+        seeds = [b"platform_config"],
+        bump = platform_config.bump,
+        constraint = !platform_config.paused @ ErrorCode::PlatformPaused
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+
+    #[account(mut)]
+    pub company: Account<'info, Company>,
+
+    #[account(
+        init,
+        payer = proposer,
+        space = 8 + 274,
+        seeds = [
+            b"company_proposal",
+            company.key().as_ref(),
+            company.proposal_count.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub proposal: Account<'info, CompanyProposal>,
+
+    #[account(mut)]
+    pub proposer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ApproveCompanyProposal<'info> {
+    #[account(
+        seeds = [b"platform_config"],
+        bump = platform_config.bump,
+        constraint = !platform_config.paused @ ErrorCode::PlatformPaused
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+
+    pub company: Account<'info, Company>,
+
+    #[account(
+        mut,
+        has_one = company,
+        seeds = [
+            b"company_proposal",
+            company.key().as_ref(),
+            proposal.proposal_id.to_le_bytes().as_ref()
+        ],
+        bump = proposal.bump
+    )]
+    pub proposal: Account<'info, CompanyProposal>,
+
+    pub approver: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteCompanyProposal<'info> {
+    #[account(
+        seeds = [b"platform_config"],
+        bump = platform_config.bump,
+        constraint = !platform_config.paused @ ErrorCode::PlatformPaused
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+
+    #[account(mut)]
+    pub company: Account<'info, Company>,
+
+    #[account(
+        mut,
+        has_one = company,
+        has_one = proposer,
+        seeds = [
+            b"company_proposal",
+            company.key().as_ref(),
+            proposal.proposal_id.to_le_bytes().as_ref()
+        ],
+        bump = proposal.bump,
+        close = proposer
+    )]
+    pub proposal: Account<'info, CompanyProposal>,
+
+    /// CHECK: Rent refund destination for the executed proposal
+    #[account(mut)]
+    pub proposer: UncheckedAccount<'info>,
+
+    // Required for RevokeAdminRole, RegrantAdminRole and RenewAdminRole
+    // actions; checked against the action
+    #[account(mut)]
+    pub admin_role: Option<Account<'info, AdminRole>>,
+
+    /// CHECK: Rent refund destination when a revoked role is closed
+    #[account(
+        mut,
+        address = company.authority @ ErrorCode::Unauthorized
+    )]
+    pub company_authority: UncheckedAccount<'info>,
+
+    pub executor: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteGrantProposal<'info> {
+    #[account(
+        seeds = [b"platform_config"],
+        bump = platform_config.bump,
+        constraint = !platform_config.paused @ ErrorCode::PlatformPaused
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+
+    #[account(mut)]
+    pub company: Account<'info, Company>,
+
+    #[account(
+        mut,
+        has_one = company,
+        has_one = proposer,
+        seeds = [
+            b"company_proposal",
+            company.key().as_ref(),
+            proposal.proposal_id.to_le_bytes().as_ref()
+        ],
+        bump = proposal.bump,
+        close = proposer
+    )]
+    pub proposal: Account<'info, CompanyProposal>,
+
+    /// CHECK: Rent refund destination for the executed proposal
+    #[account(mut)]
+    pub proposer: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + 167,
+        seeds = [
+            b"admin_role",
+            company.key().as_ref(),
+            recipient.key().as_ref()
+        ],
+        bump
+    )]
+    pub admin_role: Account<'info, AdminRole>,
+
+    /// CHECK: Must be the user named in the proposal
+    pub recipient: AccountInfo<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ConfigureTimelock<'info> {
+    #[account(
+        seeds = [b"platform_config"],
+        bump = platform_config.bump,
+        constraint = !platform_config.paused @ ErrorCode::PlatformPaused
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+
+    #[account(
+        mut,
+        constraint = company.authority == authority.key() @ ErrorCode::Unauthorized
+    )]
+    pub company: Account<'info, Company>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct QueueCompanyAction<'info> {
+    #[account(
+        seeds = [b"platform_config"],
+        bump = platform_config.bump,
+        constraint = !platform_config.paused @ ErrorCode::PlatformPaused
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+
+    #[account(mut)]
+    pub company: Account<'info, Company>,
+
+    #[account(
+        init,
+        payer = queuer,
+        space = 8 + 274,
+        seeds = [
+            b"company_proposal",
+            company.key().as_ref(),
+            company.proposal_count.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub proposal: Account<'info, CompanyProposal>,
+
+    // Optional: admins with the matching permission, otherwise must be company.authority
+    #[account(
+        seeds = [
+            b"admin_role",
+            company.key().as_ref(),
+            queuer.key().as_ref()
+        ],
+        bump
+    )]
+    pub admin_role: Option<Account<'info, AdminRole>>,
+
+    #[account(mut)]
+    pub queuer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CancelCompanyAction<'info> {
+    #[account(
+        seeds = [b"platform_config"],
+        bump = platform_config.bump,
+        constraint = !platform_config.paused @ ErrorCode::PlatformPaused
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+
+    pub company: Account<'info, Company>,
+
+    #[account(
+        mut,
+        has_one = company,
+        has_one = proposer,
+        seeds = [
+            b"company_proposal",
+            company.key().as_ref(),
+            proposal.proposal_id.to_le_bytes().as_ref()
+        ],
+        bump = proposal.bump,
+        close = proposer
+    )]
+    pub proposal: Account<'info, CompanyProposal>,
+
+    /// CHECK: Rent refund destination for the cancelled proposal
+    #[account(mut)]
+    pub proposer: UncheckedAccount<'info>,
+
+    pub canceller: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct GrantAdminRole<'info> {
+    #[account(
+        seeds = [b"platform_config"],
+        bump = platform_config.bump,
+        constraint = !platform_config.paused @ ErrorCode::PlatformPaused
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+
+    #[account(mut)]
+    pub company: Account<'info, Company>,
+
+    #[account(
+        init,
+        payer = granter,
+        space = 8 + 167,
+        seeds = [
+            b"admin_role",
+            company.key().as_ref(),
+            recipient.key().as_ref()
+        ],
+        bump
+    )]
+    pub admin_role: Account<'info, AdminRole>,
+
+    #[account(mut)]
+    pub granter: Signer<'info>,
+
+    // Omitted when the granter is company.authority. Seeds pin it to the
+    // granter, so another user's role cannot be substituted.
+    #[account(
+        seeds = [
+            b"admin_role",
+            company.key().as_ref(),
+            granter.key().as_ref()
+        ],
+        bump = granter_role.bump
+    )]
+    pub granter_role: Option<Account<'info, AdminRole>>,
+
+    /// CHECK: Recipient of admin role
+    pub recipient: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RevokeAdminRole<'info> {
+    #[account(
+        seeds = [b"platform_config"],
+        bump = platform_config.bump,
+        constraint = !platform_config.paused @ ErrorCode::PlatformPaused
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+
+    #[account(mut)]
+    pub company: Account<'info, Company>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"admin_role",
+            company.key().as_ref(),
+            admin_role.user.as_ref()
+        ],
+        bump = admin_role.bump
+    )]
+    pub admin_role: Account<'info, AdminRole>,
+
+    // Optional: admins with RevokeRole, otherwise must be company.authority
+    #[account(
+        seeds = [
+            b"admin_role",
+            company.key().as_ref(),
+            revoker.key().as_ref()
+        ],
+        bump
+    )]
+    pub revoker_role: Option<Account<'info, AdminRole>>,
+
+    pub revoker: Signer<'info>,
+
+    /// CHECK: Rent refund destination when the role is closed
+    #[account(
+        mut,
+        address = company.authority @ ErrorCode::Unauthorized
+    )]
+    pub company_authority: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RegrantAdminRole<'info> {
+    #[account(
+        seeds = [b"platform_config"],
+        bump = platform_config.bump,
+        constraint = !platform_config.paused @ ErrorCode::PlatformPaused
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+
+    #[account(mut)]
+    pub company: Account<'info, Company>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"admin_role",
+            company.key().as_ref(),
+            admin_role.user.as_ref()
+        ],
+        bump = admin_role.bump
+    )]
+    pub admin_role: Account<'info, AdminRole>,
+
+    // Optional: admins with GrantRole, otherwise must be company.authority
+    #[account(
+        seeds = [
+            b"admin_role",
+            company.key().as_ref(),
+            granter.key().as_ref()
+        ],
+        bump
+    )]
+    pub granter_role: Option<Account<'info, AdminRole>>,
+
+    #[account(mut)]
+    pub granter: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RenewAdminRole<'info> {
+    #[account(
+        seeds = [b"platform_config"],
+        bump = platform_config.bump,
+        constraint = !platform_config.paused @ ErrorCode::PlatformPaused
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+
+    pub company: Account<'info, Company>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"admin_role",
+            company.key().as_ref(),
+            admin_role.user.as_ref()
+        ],
+        bump = admin_role.bump
+    )]
+    pub admin_role: Account<'info, AdminRole>,
+
+    // Optional: admins with GrantRole, otherwise must be company.authority
+    #[account(
+        seeds = [
+            b"admin_role",
+            company.key().as_ref(),
+            renewer.key().as_ref()
+        ],
+        bump
+    )]
+    pub renewer_role: Option<Account<'info, AdminRole>>,
+
+    pub renewer: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SweepExpiredRole<'info> {
+    #[account(
+        seeds = [b"platform_config"],
+        bump = platform_config.bump,
+        constraint = !platform_config.paused @ ErrorCode::PlatformPaused
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+
+    #[account(mut)]
+    pub company: Account<'info, Company>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"admin_role",
+            company.key().as_ref(),
+            admin_role.user.as_ref()
+        ],
+        bump = admin_role.bump,
+        close = company_authority
+    )]
+    pub admin_role: Account<'info, AdminRole>,
+
+    /// CHECK: Rent refund destination
+    #[account(
+        mut,
+        address = company.authority @ ErrorCode::Unauthorized
+    )]
+    pub company_authority: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(market_id: u64)]
+pub struct CreateMarket<'info> {
+    #[account(
+        seeds = [b"platform_config"],
+        bump = platform_config.bump,
+        constraint = !platform_config.paused @ ErrorCode::PlatformPaused
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+
+    #[account(mut)]
+    pub company: Account<'info, Company>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + 1264,
+        seeds = [
+            b"market",
+            company.key().as_ref(),
+            market_id.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub market: Account<'info, Market>,
+
+    // Settlement token (e.g. USDC) for this market
+    pub mint: Account<'info, Mint>,
+
+    // Funds the LMSR subsidy; not needed for parimutuel markets
+    #[account(
+        mut,
+        constraint = creator_token_account.mint == mint.key() @ ErrorCode::WrongMint,
+        constraint = creator_token_account.owner == admin.key() @ ErrorCode::Unauthorized
+    )]
+    pub creator_token_account: Option<Account<'info, TokenAccount>>,
+
+    // Per-market escrow, owned by the market PDA
+    #[account(
+        init,
+        payer = admin,
+        seeds = [b"vault", market.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = market
+    )]
+    pub vault: Account<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + 333,
+        seeds = [b"outcomes", market.key().as_ref()],
+        bump
+    )]
+    pub outcomes: Account<'info, MarketOutcomes>,
+
+    // Verify admin role
+    #[account(
+        seeds = [
+            b"admin_role",
+            company.key().as_ref(),
+            admin.key().as_ref()
+        ],
+        bump,
+        constraint = !admin_role.revoked @ ErrorCode::RoleRevoked,
+        constraint = !role_is_expired(&admin_role) @ ErrorCode::RoleExpired,
+        constraint = admin_role.permissions & PERM_CREATE_MARKET != 0 @ ErrorCode::MissingPermission
+    )]
+    pub admin_role: Account<'info, AdminRole>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    // Rate limiting
+    #[account(
+        init_if_needed,
+        payer = admin,
+        space = 8 + 83,
+        seeds = [
+            b"rate_limit",
+            company.key().as_ref(),
+            admin.key().as_ref()
+        ],
+        bump
+    )]
+    pub rate_limit_state: Account<'info, RateLimitState>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateMarketMetadata<'info> {
+    #[account(
+        seeds = [b"platform_config"],
+        bump = platform_config.bump,
+        constraint = !platform_config.paused @ ErrorCode::PlatformPaused
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+
+    #[account(
+        mut,
+        constraint = market.company == company.key() @ ErrorCode::WrongCompany,
+        constraint = market.creator == admin.key() @ ErrorCode::Unauthorized
+    )]
+    pub market: Account<'info, Market>,
+
+    pub company: Account<'info, Company>,
+
+    // Verify admin role
+    #[account(
+        seeds = [
+            b"admin_role",
+            company.key().as_ref(),
+            admin.key().as_ref()
+        ],
+        bump,
+        constraint = !admin_role.revoked @ ErrorCode::RoleRevoked,
+        constraint = !role_is_expired(&admin_role) @ ErrorCode::RoleExpired,
+        constraint = admin_role.permissions & PERM_CREATE_MARKET != 0 @ ErrorCode::MissingPermission
+    )]
+    pub admin_role: Account<'info, AdminRole>,
+
+    pub admin: Signer<'info>,
+}
+
+// Every outcome's OutcomeLedger follows as remaining accounts
+#[derive(Accounts)]
+pub struct OpenMarket<'info> {
+    #[account(
+        seeds = [b"platform_config"],
+        bump = platform_config.bump,
+        constraint = !platform_config.paused @ ErrorCode::PlatformPaused
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+
+    #[account(
+        mut,
+        constraint = market.company == company.key() @ ErrorCode::WrongCompany
+    )]
+    pub market: Account<'info, Market>,
+
+    pub company: Account<'info, Company>,
+
+    // Verify admin role
+    #[account(
+        seeds = [
+            b"admin_role",
+            company.key().as_ref(),
+            admin.key().as_ref()
+        ],
+        bump,
+        constraint = !admin_role.revoked @ ErrorCode::RoleRevoked,
+        constraint = !role_is_expired(&admin_role) @ ErrorCode::RoleExpired,
+        constraint = admin_role.permissions & PERM_CREATE_MARKET != 0 @ ErrorCode::MissingPermission
+    )]
+    pub admin_role: Account<'info, AdminRole>,
+
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ProposeOutcome<'info> {
+    #[account(
+        seeds = [b"platform_config"],
+        bump = platform_config.bump,
+        constraint = !platform_config.paused @ ErrorCode::PlatformPaused
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+
+    #[account(
+        mut,
+        constraint = market.company == company.key() @ ErrorCode::WrongCompany
+    )]
+    pub market: Account<'info, Market>,
+
+    pub company: Account<'info, Company>,
+
+    // Verify admin role
+    #[account(
+        seeds = [
+            b"admin_role",
+            company.key().as_ref(),
+            proposer.key().as_ref()
+        ],
+        bump,
+        constraint = !admin_role.revoked @ ErrorCode::RoleRevoked,
+        constraint = !role_is_expired(&admin_role) @ ErrorCode::RoleExpired,
+        constraint = admin_role.permissions & PERM_RESOLVE_MARKET != 0 @ ErrorCode::MissingPermission
+    )]
+    pub admin_role: Account<'info, AdminRole>,
+
+    /// CHECK: Proposer's bet PDA for this market; must not exist
+    #[account(
+        seeds = [
+            b"bet",
+            market.key().as_ref(),
+            proposer.key().as_ref()
+        ],
+        bump
+    )]
+    pub proposer_bet: UncheckedAccount<'info>,
+
+    #[account(address = market.mint @ ErrorCode::WrongMint)]
+    pub mint: Account<'info, Mint>,
+
+    // Holds proposer and challenger bonds, separate from the betting vault
+    #[account(
+        init,
+        payer = proposer,
+        seeds = [b"bond_vault", market.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = market
+    )]
+    pub bond_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = proposer_token_account.mint == market.mint @ ErrorCode::WrongMint,
+        constraint = proposer_token_account.owner == proposer.key() @ ErrorCode::Unauthorized
+    )]
+    pub proposer_token_account: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub proposer: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct DisputeOutcome<'info> {
+    #[account(
+        seeds = [b"platform_config"],
+        bump = platform_config.bump,
+        constraint = !platform_config.paused @ ErrorCode::PlatformPaused
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+
+    #[account(
+        mut,
+        constraint = market.company == company.key() @ ErrorCode::WrongCompany
+    )]
+    pub market: Account<'info, Market>,
+
+    pub company: Account<'info, Company>,
+
+    // Optional admin role check (if None, requires merkle proof)
+    #[account(
+        seeds = [
+            b"admin_role",
+            company.key().as_ref(),
+            challenger.key().as_ref()
+        ],
+        bump
+    )]
+    pub admin_role: Option<Account<'info, AdminRole>>,
+
+    #[account(
+        mut,
+        seeds = [b"bond_vault", market.key().as_ref()],
+        bump = market.bond_vault_bump
+    )]
+    pub bond_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = challenger_token_account.mint == market.mint @ ErrorCode::WrongMint,
+        constraint = challenger_token_account.owner == challenger.key() @ ErrorCode::Unauthorized
+    )]
+    pub challenger_token_account: Account<'info, TokenAccount>,
+
+    pub challenger: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+// Every outcome's OutcomeLedger follows as remaining accounts
+#[derive(Accounts)]
+pub struct FinalizeOutcome<'info> {
+    #[account(
+        seeds = [b"platform_config"],
+        bump = platform_config.bump,
+        constraint = !platform_config.paused @ ErrorCode::PlatformPaused
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+
+    #[account(mut)]
+    pub market: Account<'info, Market>,
+
+    #[account(address = market.company @ ErrorCode::WrongCompany)]
+    pub company: Account<'info, Company>,
+
+    #[account(
+        mut,
+        seeds = [b"bond_vault", market.key().as_ref()],
+        bump = market.bond_vault_bump
+    )]
+    pub bond_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = Some(proposer_token_account.owner) == market.proposer @ ErrorCode::Unauthorized,
+        constraint = proposer_token_account.mint == market.mint @ ErrorCode::WrongMint
+    )]
+    pub proposer_token_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+// Every outcome's OutcomeLedger follows as remaining accounts
+#[derive(Accounts)]
+pub struct ArbitrateOutcome<'info> {
+    #[account(
+        seeds = [b"platform_config"],
+        bump = platform_config.bump,
+        constraint = !platform_config.paused @ ErrorCode::PlatformPaused
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+
+    #[account(
+        mut,
+        constraint = market.company == company.key() @ ErrorCode::WrongCompany
+    )]
+    pub market: Account<'info, Market>,
+
+    pub company: Account<'info, Company>,
+
+    // Optional: admins with ResolveMarket, otherwise must be company.authority
+    // of a single-key company
+    #[account(
+        seeds = [
+            b"admin_role",
+            company.key().as_ref(),
+            authority.key().as_ref()
+        ],
+        bump
+    )]
+    pub admin_role: Option<Account<'info, AdminRole>>,
+
+    /// CHECK: Arbitrator's bet PDA for this market; must not exist
+    #[account(
+        seeds = [
+            b"bet",
+            market.key().as_ref(),
+            authority.key().as_ref()
+        ],
+        bump
+    )]
+    pub arbitrator_bet: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"bond_vault", market.key().as_ref()],
+        bump = market.bond_vault_bump
+    )]
+    pub bond_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = Some(proposer_token_account.owner) == market.proposer @ ErrorCode::Unauthorized,
+        constraint = proposer_token_account.mint == market.mint @ ErrorCode::WrongMint
+    )]
+    pub proposer_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = Some(challenger_token_account.owner) == market.challenger @ ErrorCode::Unauthorized,
+        constraint = challenger_token_account.mint == market.mint @ ErrorCode::WrongMint
+    )]
+    pub challenger_token_account: Account<'info, TokenAccount>,
+
+    pub authority: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+// Every outcome's OutcomeLedger follows as remaining accounts
+#[derive(Accounts)]
+pub struct SubmitResolutionVote<'info> {
+    #[account(
+        seeds = [b"platform_config"],
+        bump = platform_config.bump,
+        constraint = !platform_config.paused @ ErrorCode::PlatformPaused
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+
+    #[account(
+        mut,
+        constraint = market.company == company.key() @ ErrorCode::WrongCompany
+    )]
+    pub market: Account<'info, Market>,
+
+    pub company: Account<'info, Company>,
+
+    #[account(
+        init,
+        payer = resolver,
+        space = 8 + 82,
+        seeds = [
+            b"resolution_vote",
+            market.key().as_ref(),
+            resolver.key().as_ref()
+        ],
+        bump
+    )]
+    pub vote: Account<'info, ResolutionVote>,
+
+    // Verify admin role
+    #[account(
+        seeds = [
+            b"admin_role",
+            company.key().as_ref(),
+            resolver.key().as_ref()
+        ],
+        bump,
+        constraint = !admin_role.revoked @ ErrorCode::RoleRevoked,
+        constraint = !role_is_expired(&admin_role) @ ErrorCode::RoleExpired,
+        constraint = admin_role.permissions & PERM_RESOLVE_MARKET != 0 @ ErrorCode::MissingPermission
+    )]
     pub admin_role: Account<'info, AdminRole>,
 
-    #[account(
-        constraint = company.authority == revoker.key() @ ErrorCode::Unauthorized
-    )]
-    pub revoker: Signer<'info>,
+    /// CHECK: Resolver's bet PDA for this market; must not exist
+    #[account(
+        seeds = [
+            b"bet",
+            market.key().as_ref(),
+            resolver.key().as_ref()
+        ],
+        bump
+    )]
+    pub resolver_bet: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub resolver: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ExpireResolutionVote<'info> {
+    #[account(
+        seeds = [b"platform_config"],
+        bump = platform_config.bump,
+        constraint = !platform_config.paused @ ErrorCode::PlatformPaused
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+
+    #[account(
+        mut,
+        constraint = market.company == company.key() @ ErrorCode::WrongCompany
+    )]
+    pub market: Account<'info, Market>,
+
+    pub company: Account<'info, Company>,
+}
+
+// Every outcome's OutcomeLedger follows as remaining accounts
+#[derive(Accounts)]
+pub struct ResolveWithOracle<'info> {
+    #[account(
+        seeds = [b"platform_config"],
+        bump = platform_config.bump,
+        constraint = !platform_config.paused @ ErrorCode::PlatformPaused
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+
+    #[account(
+        mut,
+        constraint = market.company == company.key() @ ErrorCode::WrongCompany
+    )]
+    pub market: Account<'info, Market>,
+
+    pub company: Account<'info, Company>,
+
+    /// CHECK: Instructions sysvar, used to introspect the Ed25519 instruction
+    #[account(address = instructions_sysvar::ID)]
+    pub instructions: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(outcome: u8)]
+pub struct InitOutcomeLedger<'info> {
+    pub market: Account<'info, Market>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + 94,
+        seeds = [b"ledger", market.key().as_ref(), outcome.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub ledger: Account<'info, OutcomeLedger>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+// Every outcome's OutcomeLedger follows as remaining accounts
+#[derive(Accounts)]
+pub struct SyncMarketTotals<'info> {
+    #[account(mut)]
+    pub market: Account<'info, Market>,
+}
+
+#[derive(Accounts)]
+pub struct CancelMarket<'info> {
+    #[account(
+        seeds = [b"platform_config"],
+        bump = platform_config.bump,
+        constraint = !platform_config.paused @ ErrorCode::PlatformPaused
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+
+    #[account(
+        mut,
+        constraint = market.company == company.key() @ ErrorCode::WrongCompany
+    )]
+    pub market: Account<'info, Market>,
+
+    pub company: Account<'info, Company>,
+
+    // Optional: admins with ResolveMarket, otherwise must be company.authority
+    // of a single-key company
+    #[account(
+        seeds = [
+            b"admin_role",
+            company.key().as_ref(),
+            authority.key().as_ref()
+        ],
+        bump
+    )]
+    pub admin_role: Option<Account<'info, AdminRole>>,
+
+    /// CHECK: Canceller's bet PDA for this market; must not exist
+    #[account(
+        seeds = [
+            b"bet",
+            market.key().as_ref(),
+            authority.key().as_ref()
+        ],
+        bump
+    )]
+    pub authority_bet: UncheckedAccount<'info>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(amount: u64, outcome: u8)]
+pub struct PlaceBet<'info> {
+    #[account(
+        seeds = [b"platform_config"],
+        bump = platform_config.bump,
+        constraint = !platform_config.paused @ ErrorCode::PlatformPaused
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+
+    #[account(
+        constraint = market.company == company.key() @ ErrorCode::WrongCompany
+    )]
+    pub market: Account<'info, Market>,
+
+    pub company: Account<'info, Company>,
+
+    // One position per user per market; repeat bets top it up
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + 163,
+        seeds = [
+            b"bet",
+            market.key().as_ref(),
+            user.key().as_ref()
+        ],
+        bump
+    )]
+    pub bet: Account<'info, Bet>,
+
+    // This outcome's pool shard
+    #[account(
+        mut,
+        seeds = [b"ledger", market.key().as_ref(), outcome.to_le_bytes().as_ref()],
+        bump = ledger.bump
+    )]
+    pub ledger: Account<'info, OutcomeLedger>,
+
+    // Optional admin role check (if None, requires merkle proof)
+    #[account(
+        seeds = [
+            b"admin_role",
+            company.key().as_ref(),
+            user.key().as_ref()
+        ],
+        bump
+    )]
+    pub admin_role: Option<Account<'info, AdminRole>>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", market.key().as_ref()],
+        bump = market.vault_bump
+    )]
+    pub vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = user_token_account.mint == market.mint @ ErrorCode::WrongMint,
+        constraint = user_token_account.owner == user.key() @ ErrorCode::Unauthorized
+    )]
+    pub user_token_account: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(outcome: u8)]
+pub struct WithdrawBet<'info> {
+    #[account(
+        seeds = [b"platform_config"],
+        bump = platform_config.bump,
+        constraint = !platform_config.paused @ ErrorCode::PlatformPaused
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+
+    #[account(
+        constraint = market.company == company.key() @ ErrorCode::WrongCompany
+    )]
+    pub market: Account<'info, Market>,
+
+    pub company: Account<'info, Company>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"bet",
+            market.key().as_ref(),
+            user.key().as_ref()
+        ],
+        bump = bet.bump,
+        constraint = bet.user == user.key() @ ErrorCode::Unauthorized
+    )]
+    pub bet: Account<'info, Bet>,
+
+    // This outcome's pool shard
+    #[account(
+        mut,
+        seeds = [b"ledger", market.key().as_ref(), outcome.to_le_bytes().as_ref()],
+        bump = ledger.bump
+    )]
+    pub ledger: Account<'info, OutcomeLedger>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", market.key().as_ref()],
+        bump = market.vault_bump
+    )]
+    pub vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = user_token_account.mint == market.mint @ ErrorCode::WrongMint,
+        constraint = user_token_account.owner == user.key() @ ErrorCode::Unauthorized
+    )]
+    pub user_token_account: Account<'info, TokenAccount>,
+
+    pub user: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+#[instruction(outcome: u8)]
+pub struct BuyShares<'info> {
+    #[account(
+        seeds = [b"platform_config"],
+        bump = platform_config.bump,
+        constraint = !platform_config.paused @ ErrorCode::PlatformPaused
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+
+    #[account(
+        mut,
+        constraint = market.company == company.key() @ ErrorCode::WrongCompany
+    )]
+    pub market: Account<'info, Market>,
+
+    pub company: Account<'info, Company>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + 163,
+        seeds = [
+            b"bet",
+            market.key().as_ref(),
+            user.key().as_ref()
+        ],
+        bump
+    )]
+    pub bet: Account<'info, Bet>,
+
+    // This outcome's pool shard
+    #[account(
+        mut,
+        seeds = [b"ledger", market.key().as_ref(), outcome.to_le_bytes().as_ref()],
+        bump = ledger.bump
+    )]
+    pub ledger: Account<'info, OutcomeLedger>,
+
+    // Optional admin role check (if None, requires merkle proof)
+    #[account(
+        seeds = [
+            b"admin_role",
+            company.key().as_ref(),
+            user.key().as_ref()
+        ],
+        bump
+    )]
+    pub admin_role: Option<Account<'info, AdminRole>>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", market.key().as_ref()],
+        bump = market.vault_bump
+    )]
+    pub vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = user_token_account.mint == market.mint @ ErrorCode::WrongMint,
+        constraint = user_token_account.owner == user.key() @ ErrorCode::Unauthorized
+    )]
+    pub user_token_account: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(outcome: u8)]
+pub struct SellShares<'info> {
+    #[account(
+        seeds = [b"platform_config"],
+        bump = platform_config.bump,
+        constraint = !platform_config.paused @ ErrorCode::PlatformPaused
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+
+    #[account(
+        mut,
+        constraint = market.company == company.key() @ ErrorCode::WrongCompany
+    )]
+    pub market: Account<'info, Market>,
+
+    pub company: Account<'info, Company>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"bet",
+            market.key().as_ref(),
+            user.key().as_ref()
+        ],
+        bump = bet.bump,
+        constraint = bet.user == user.key() @ ErrorCode::Unauthorized
+    )]
+    pub bet: Account<'info, Bet>,
+
+    // This outcome's pool shard
+    #[account(
+        mut,
+        seeds = [b"ledger", market.key().as_ref(), outcome.to_le_bytes().as_ref()],
+        bump = ledger.bump
+    )]
+    pub ledger: Account<'info, OutcomeLedger>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", market.key().as_ref()],
+        bump = market.vault_bump
+    )]
+    pub vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = user_token_account.mint == market.mint @ ErrorCode::WrongMint,
+        constraint = user_token_account.owner == user.key() @ ErrorCode::Unauthorized
+    )]
+    pub user_token_account: Account<'info, TokenAccount>,
+
+    pub user: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimWinnings<'info> {
+    #[account(
+        seeds = [b"platform_config"],
+        bump = platform_config.bump,
+        constraint = !platform_config.paused @ ErrorCode::PlatformPaused
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"bet",
+            bet.market.as_ref(),
+            user.key().as_ref()
+        ],
+        bump = bet.bump,
+        constraint = bet.user == user.key() @ ErrorCode::Unauthorized
+    )]
+    pub bet: Account<'info, Bet>,
+
+    #[account(
+        mut,
+        constraint = market.key() == bet.market @ ErrorCode::WrongMarket
+    )]
+    pub market: Account<'info, Market>,
+
+    #[account(address = market.company @ ErrorCode::WrongCompany)]
+    pub company: Account<'info, Company>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", market.key().as_ref()],
+        bump = market.vault_bump
+    )]
+    pub vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = user_token_account.mint == market.mint @ ErrorCode::WrongMint,
+        constraint = user_token_account.owner == user.key() @ ErrorCode::Unauthorized
+    )]
+    pub user_token_account: Account<'info, TokenAccount>,
+
+    pub user: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct RefundBet<'info> {
+    #[account(
+        seeds = [b"platform_config"],
+        bump = platform_config.bump,
+        constraint = !platform_config.paused @ ErrorCode::PlatformPaused
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"bet",
+            bet.market.as_ref(),
+            user.key().as_ref()
+        ],
+        bump = bet.bump,
+        constraint = bet.user == user.key() @ ErrorCode::Unauthorized
+    )]
+    pub bet: Account<'info, Bet>,
+
+    #[account(
+        mut,
+        constraint = market.key() == bet.market @ ErrorCode::WrongMarket
+    )]
+    pub market: Account<'info, Market>,
+
+    #[account(address = market.company @ ErrorCode::WrongCompany)]
+    pub company: Account<'info, Company>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", market.key().as_ref()],
+        bump = market.vault_bump
+    )]
+    pub vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = user_token_account.mint == market.mint @ ErrorCode::WrongMint,
+        constraint = user_token_account.owner == user.key() @ ErrorCode::Unauthorized
+    )]
+    pub user_token_account: Account<'info, TokenAccount>,
+
+    pub user: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+// Every outcome's OutcomeLedger follows as remaining accounts
+#[derive(Accounts)]
+pub struct SweepDust<'info> {
+    #[account(
+        seeds = [b"platform_config"],
+        bump = platform_config.bump,
+        constraint = !platform_config.paused @ ErrorCode::PlatformPaused
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+
+    #[account(
+        mut,
+        constraint = market.company == company.key() @ ErrorCode::WrongCompany
+    )]
+    pub market: Account<'info, Market>,
+
+    pub company: Account<'info, Company>,
+
+    // Optional: admins with ManageTreasury, otherwise must be company.authority
+    #[account(
+        seeds = [
+            b"admin_role",
+            company.key().as_ref(),
+            authority.key().as_ref()
+        ],
+        bump
+    )]
+    pub admin_role: Option<Account<'info, AdminRole>>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", market.key().as_ref()],
+        bump = market.vault_bump
+    )]
+    pub vault: Account<'info, TokenAccount>,
+
+    #[account(address = market.mint @ ErrorCode::WrongMint)]
+    pub mint: Account<'info, Mint>,
+
+    // Receives an LMSR market's leftover subsidy; not needed otherwise
+    #[account(
+        mut,
+        constraint = creator_token_account.mint == market.mint @ ErrorCode::WrongMint,
+        constraint = creator_token_account.owner == market.creator @ ErrorCode::Unauthorized
+    )]
+    pub creator_token_account: Option<Account<'info, TokenAccount>>,
+
+    // Dust always lands in the company treasury, never a caller-chosen account
+    #[account(
+        init_if_needed,
+        payer = payer,
+        seeds = [b"company_treasury", company.key().as_ref(), mint.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = company
+    )]
+    pub company_treasury: Account<'info, TokenAccount>,
+
+    pub authority: Signer<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimPlatformFees<'info> {
+    #[account(
+        seeds = [b"platform_config"],
+        bump = platform_config.bump,
+        constraint = !platform_config.paused @ ErrorCode::PlatformPaused
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+
+    #[account(mut)]
+    pub market: Account<'info, Market>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", market.key().as_ref()],
+        bump = market.vault_bump
+    )]
+    pub vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = treasury_token_account.owner == platform_config.treasury
+            @ ErrorCode::WrongTreasury,
+        constraint = treasury_token_account.mint == market.mint @ ErrorCode::WrongMint
+    )]
+    pub treasury_token_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimCompanyFees<'info> {
+    #[account(
+        seeds = [b"platform_config"],
+        bump = platform_config.bump,
+        constraint = !platform_config.paused @ ErrorCode::PlatformPaused
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+
+    #[account(
+        mut,
+        constraint = market.company == company.key() @ ErrorCode::WrongCompany
+    )]
+    pub market: Account<'info, Market>,
+
+    pub company: Account<'info, Company>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", market.key().as_ref()],
+        bump = market.vault_bump
+    )]
+    pub vault: Account<'info, TokenAccount>,
+
+    #[account(address = market.mint @ ErrorCode::WrongMint)]
+    pub mint: Account<'info, Mint>,
+
+    // Company treasury: one per company and mint, owned by the company PDA
+    #[account(
+        init_if_needed,
+        payer = payer,
+        seeds = [b"company_treasury", company.key().as_ref(), mint.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = company
+    )]
+    pub company_treasury: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawCompanyTreasury<'info> {
+    #[account(
+        seeds = [b"platform_config"],
+        bump = platform_config.bump,
+        constraint = !platform_config.paused @ ErrorCode::PlatformPaused
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+
+    pub company: Account<'info, Company>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"company_treasury",
+            company.key().as_ref(),
+            company_treasury.mint.as_ref()
+        ],
+        bump
+    )]
+    pub company_treasury: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = destination.mint == company_treasury.mint @ ErrorCode::WrongMint
+    )]
+    pub destination: Account<'info, TokenAccount>,
+
+    // Optional: admins with ManageTreasury, otherwise must be company.authority
+    #[account(
+        seeds = [
+            b"admin_role",
+            company.key().as_ref(),
+            authority.key().as_ref()
+        ],
+        bump
+    )]
+    pub admin_role: Option<Account<'info, AdminRole>>,
+
+    pub authority: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+// ============================================================================
+// HELPER FUNCTIONS
+// ============================================================================
+
+fn hash_leaf(pubkey: &Pubkey) -> [u8; 32] {
+    keccak::hash(pubkey.as_ref()).to_bytes()
+}
+
+fn verify_merkle_proof(proof: &[[u8; 32]], root: [u8; 32], leaf: [u8; 32]) -> bool {
+    let mut computed_hash = leaf;
+
+    for proof_element in proof.iter() {
+        computed_hash = if computed_hash <= *proof_element {
+            keccak::hashv(&[&computed_hash, proof_element]).to_bytes()
+        } else {
+            keccak::hashv(&[proof_element, &computed_hash]).to_bytes()
+        };
+    }
+
+    computed_hash == root
+}
+
+/// Winner's share of the pool: floor(stake * total_pool / winning_total).
+/// Rounding down on every claim means the sum of payouts can never exceed
+/// the pool; the leftover is swept to the company treasury.
+fn parimutuel_payout(stake: u64, winning_total: u64, total_pool: u64) -> Result<u64> {
+    require!(winning_total > 0, ErrorCode::LosingBet);
+    require!(stake <= winning_total, ErrorCode::Overflow);
+
+    let payout = (stake as u128)
+        .checked_mul(total_pool as u128)
+        .ok_or(ErrorCode::Overflow)?
+        / winning_total as u128;
+
+    u64::try_from(payout).map_err(|_| error!(ErrorCode::Overflow))
+}
+
+/// Scalar settlement: Long receives (v - lower) / (upper - lower) of the
+/// pool and Short the rest, with v clamped to the range. Both sides round
+/// down so together they never exceed the pool. If only one side has stake
+/// it takes the whole pool, since nobody could claim the other share.
+fn scalar_side_pools(market: &Market) -> Result<[u64; 2]> {
+    let value = market.scalar_value.ok_or(ErrorCode::NotResolved)?;
+    let lower = market.scalar_lower as i128;
+    let upper = market.scalar_upper as i128;
+    // Both fit in u64 since lower < upper, so pool * part fits in u128
+    let range = (upper - lower) as u128;
+    let long_part = ((value as i128).clamp(lower, upper) - lower) as u128;
+    let pool = market.total_volume as u128;
+
+    let mut pools = [0u64; 2];
+    for (side, other) in [(SCALAR_SHORT, SCALAR_LONG), (SCALAR_LONG, SCALAR_SHORT)] {
+        if market.outcome_stakes[side] == 0 && market.outcome_stakes[other] > 0 {
+            pools[other] = market.total_volume;
+            return Ok(pools);
+        }
+    }
+    let split = |part: u128| -> Result<u64> {
+        let share = pool
+            .checked_mul(part)
+            .and_then(|scaled| scaled.checked_div(range))
+            .ok_or(ErrorCode::Overflow)?;
+        Ok(share as u64)
+    };
+    pools[SCALAR_LONG] = split(long_part)?;
+    pools[SCALAR_SHORT] = split(range - long_part)?;
+    Ok(pools)
+}
+
+/// A position counts as a bettor while it holds units in any outcome.
+fn position_is_empty(bet: &Bet) -> bool {
+    bet.outcome_amounts.iter().all(|&units| units == 0)
+}
+
+/// Adds `units` (stake, or LMSR shares) to outcome `i` and `basis` to the
+/// position's cost basis, keeping the shard's bettor counters in step, so
+/// a user who fully exits and re-enters is counted again exactly once.
+fn open_stake(
+    ledger: &mut OutcomeLedger,
+    bet: &mut Bet,
+    i: usize,
+    units: u64,
+    basis: u64,
+) -> Result<()> {
+    let is_new_position = position_is_empty(bet);
+    let is_new_outcome = bet.outcome_amounts[i] == 0;
+
+    bet.outcome_amounts[i] = bet.outcome_amounts[i]
+        .checked_add(units)
+        .ok_or(ErrorCode::Overflow)?;
+    bet.amount = bet.amount.checked_add(basis).ok_or(ErrorCode::Overflow)?;
+
+    ledger.basis_in = ledger
+        .basis_in
+        .checked_add(basis)
+        .ok_or(ErrorCode::Overflow)?;
+    if is_new_outcome {
+        ledger.bettors = ledger.bettors.checked_add(1).ok_or(ErrorCode::Overflow)?;
+    }
+    if is_new_position {
+        ledger.positions_opened = ledger
+            .positions_opened
+            .checked_add(1)
+            .ok_or(ErrorCode::Overflow)?;
+    }
+
+    Ok(())
+}
+
+/// Inverse of open_stake. The basis released may have been added on another
+/// shard, which is why the market-wide counters are in/out pairs.
+fn close_stake(
+    ledger: &mut OutcomeLedger,
+    bet: &mut Bet,
+    i: usize,
+    units: u64,
+    basis: u64,
+) -> Result<()> {
+    bet.outcome_amounts[i] = bet.outcome_amounts[i]
+        .checked_sub(units)
+        .ok_or(ErrorCode::InsufficientPosition)?;
+    bet.amount = bet.amount.checked_sub(basis).ok_or(ErrorCode::Underflow)?;
+
+    ledger.basis_out = ledger
+        .basis_out
+        .checked_add(basis)
+        .ok_or(ErrorCode::Overflow)?;
+    if bet.outcome_amounts[i] == 0 {
+        ledger.bettors = ledger.bettors.checked_sub(1).ok_or(ErrorCode::Underflow)?;
+    }
+    if position_is_empty(bet) {
+        ledger.positions_closed = ledger
+            .positions_closed
+            .checked_add(1)
+            .ok_or(ErrorCode::Overflow)?;
+    }
+
+    Ok(())
+}
+
+/// Market-wide pool totals, summed across every OutcomeLedger of a market.
+#[derive(Clone, Copy, Default)]
+struct PoolTotals {
+    outcome_stakes: [u64; MAX_OUTCOMES],
+    bettor_counts: [u32; MAX_OUTCOMES],
+    total_volume: u64,
+    total_bettors: u64,
+    open_interest: u64, // sum of open Bet.amount
+}
+
+fn pool_totals(ledgers: &[OutcomeLedger]) -> Result<PoolTotals> {
+    let mut totals = PoolTotals::default();
+    let (mut opened, mut closed, mut basis_in, mut basis_out) = (0u64, 0u64, 0u64, 0u64);
+
+    for (i, ledger) in ledgers.iter().enumerate() {
+        totals.outcome_stakes[i] = ledger.stake;
+        totals.bettor_counts[i] = ledger.bettors;
+        totals.total_volume = totals
+            .total_volume
+            .checked_add(ledger.volume)
+            .ok_or(ErrorCode::Overflow)?;
+        opened = opened.checked_add(ledger.positions_opened).ok_or(ErrorCode::Overflow)?;
+        closed = closed.checked_add(ledger.positions_closed).ok_or(ErrorCode::Overflow)?;
+        basis_in = basis_in.checked_add(ledger.basis_in).ok_or(ErrorCode::Overflow)?;
+        basis_out = basis_out.checked_add(ledger.basis_out).ok_or(ErrorCode::Overflow)?;
+    }
+    totals.total_bettors = opened.checked_sub(closed).ok_or(ErrorCode::Underflow)?;
+    totals.open_interest = basis_in.checked_sub(basis_out).ok_or(ErrorCode::Underflow)?;
+
+    Ok(totals)
+}
+
+/// Reads `market`'s outcome ledgers, passed as remaining accounts in
+/// outcome order. Leaving a shard out would skew settlement, so the caller
+/// must pass exactly one per outcome.
+fn load_outcome_ledgers(
+    market: &Account<Market>,
+    accounts: &[AccountInfo],
+) -> Result<Vec<OutcomeLedger>> {
+    require!(
+        accounts.len() == market.num_outcomes as usize,
+        ErrorCode::InvalidOutcomeLedgers
+    );
+
+    let mut ledgers = Vec::with_capacity(accounts.len());
+    for (i, info) in accounts.iter().enumerate() {
+        require!(info.owner == &crate::ID, ErrorCode::InvalidOutcomeLedgers);
+        let ledger = OutcomeLedger::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+        require!(
+            ledger.market == market.key() && ledger.outcome as usize == i,
+            ErrorCode::InvalidOutcomeLedgers
+        );
+        ledgers.push(ledger);
+    }
+
+    Ok(ledgers)
+}
+
+fn load_pool_totals(market: &Account<Market>, accounts: &[AccountInfo]) -> Result<PoolTotals> {
+    pool_totals(&load_outcome_ledgers(market, accounts)?)
+}
+
+fn record_pool_totals(market: &mut Market, totals: &PoolTotals) {
+    market.outcome_stakes = match market.market_type {
+        MarketType::Parimutuel | MarketType::Scalar => totals.outcome_stakes,
+        MarketType::Lmsr => market.lmsr_shares,
+    };
+    market.total_volume = totals.total_volume;
+}
+
+/// Returns (payout, settled stake) for a position in a resolved market.
+/// Settled stake is what counts towards `claimed_stake` for sweep_dust.
+fn position_payout(market: &Market, bet: &Bet) -> Result<(u64, u64)> {
+    match market.market_type {
+        MarketType::Parimutuel | MarketType::Lmsr => {
+            let winning = market.winning_outcome.ok_or(ErrorCode::NotResolved)? as usize;
+            let stake = bet.outcome_amounts[winning];
+            if stake == 0 {
+                return Ok((0, 0));
+            }
+
+            let payout = match market.market_type {
+                // Pro-rata share of the whole pool, rounded down; remainder is dust
+                MarketType::Parimutuel => {
+                    parimutuel_payout(stake, market.outcome_stakes[winning], market.total_volume)?
+                }
+                // Each winning share redeems for one base unit
+                _ => stake,
+            };
+            Ok((payout, stake))
+        }
+        MarketType::Scalar => {
+            let pools = scalar_side_pools(market)?;
+            let mut payout = 0u64;
+            let mut stake = 0u64;
+            for side in [SCALAR_SHORT, SCALAR_LONG] {
+                let side_stake = bet.outcome_amounts[side];
+                if side_stake == 0 || pools[side] == 0 {
+                    continue;
+                }
+                let side_payout =
+                    parimutuel_payout(side_stake, market.outcome_stakes[side], pools[side])?;
+                payout = payout.checked_add(side_payout).ok_or(ErrorCode::Overflow)?;
+                stake = stake.checked_add(side_stake).ok_or(ErrorCode::Overflow)?;
+            }
+            Ok((payout, stake))
+        }
+    }
+}
+
+/// Total stake that position_payout will settle across all bettors.
+fn winning_stake_total(market: &Market) -> Result<u64> {
+    match market.market_type {
+        MarketType::Scalar => {
+            let pools = scalar_side_pools(market)?;
+            let mut total = 0u64;
+            for side in [SCALAR_SHORT, SCALAR_LONG] {
+                if pools[side] > 0 {
+                    total = total
+                        .checked_add(market.outcome_stakes[side])
+                        .ok_or(ErrorCode::Overflow)?;
+                }
+            }
+            Ok(total)
+        }
+        _ => {
+            let winning = market.winning_outcome.ok_or(ErrorCode::NotResolved)?;
+            Ok(market.outcome_stakes[winning as usize])
+        }
+    }
+}
+
+/// Stored status with time-based transitions applied: an Open market past
+/// its close_time is Closed even if no instruction has written that yet.
+fn market_status(market: &Market, now: i64) -> MarketStatus {
+    if market.status == MarketStatus::Open && now >= market.close_time {
+        MarketStatus::Closed
+    } else {
+        market.status
+    }
+}
+
+/// The only place Market.status is written after creation. Anything not
+/// listed here is an illegal transition.
+fn transition_market(market: &mut Market, to: MarketStatus, now: i64) -> Result<()> {
+    use MarketStatus::*;
+
+    let from = market_status(market, now);
+    let allowed = matches!(
+        (from, to),
+        (Draft, Open)
+            | (Draft, Cancelled)
+            | (Open, Cancelled)
+            | (Closed, Proposed)
+            | (Closed, Resolved)
+            | (Closed, Cancelled)
+            | (Proposed, Disputed)
+            | (Proposed, Resolved)
+            | (Proposed, Cancelled)
+            | (Disputed, Resolved)
+            | (Disputed, Cancelled)
+            | (Resolved, Finalized)
+            | (Cancelled, Finalized)
+    );
+    require!(allowed, ErrorCode::InvalidStateTransition);
+
+    market.status = to;
+    Ok(())
+}
+
+/// Checks that the previous instruction is an Ed25519 program verification
+/// of `message` by `signer`. The Ed25519 program has already verified the
+/// signature; we only confirm it covered the right key and bytes. All data
+/// must live inside that instruction (index u16::MAX) so offsets cannot
+/// point at attacker-controlled instructions.
+fn verify_oracle_signature(
+    instructions: &AccountInfo,
+    signer: &Pubkey,
+    message: &[u8],
+) -> Result<()> {
+    let current = load_current_index_checked(instructions)? as usize;
+    require!(current > 0, ErrorCode::MissingOracleSignature);
+
+    let ix = load_instruction_at_checked(current - 1, instructions)?;
+    require!(
+        ix.program_id == ed25519_program::ID && ix.accounts.is_empty(),
+        ErrorCode::MissingOracleSignature
+    );
+
+    // Header: num_signatures (u8), padding (u8), then one 14-byte offsets struct
+    let data = &ix.data;
+    require!(data.len() >= 16 && data[0] == 1, ErrorCode::InvalidOracleSignature);
+
+    let read_u16 = |at: usize| u16::from_le_bytes([data[at], data[at + 1]]);
+    let signature_ix = read_u16(4);
+    let pubkey_offset = read_u16(6) as usize;
+    let pubkey_ix = read_u16(8);
+    let message_offset = read_u16(10) as usize;
+    let message_size = read_u16(12) as usize;
+    let message_ix = read_u16(14);
+
+    require!(
+        signature_ix == u16::MAX && pubkey_ix == u16::MAX && message_ix == u16::MAX,
+        ErrorCode::InvalidOracleSignature
+    );
+
+    let signed_pubkey = data
+        .get(pubkey_offset..pubkey_offset + 32)
+        .ok_or(ErrorCode::InvalidOracleSignature)?;
+    let signed_message = data
+        .get(message_offset..message_offset + message_size)
+        .ok_or(ErrorCode::InvalidOracleSignature)?;
+
+    require!(
+        signed_pubkey == signer.as_ref(),
+        ErrorCode::InvalidOracleSignature
+    );
+    require!(signed_message == message, ErrorCode::InvalidOracleReport);
+
+    Ok(())
+}
+
+/// Records the final outcome and pool totals for claim_winnings.
+/// `value` must already have passed validate_resolution_value.
+fn settle_market(
+    market: &mut Account<Market>,
+    totals: &PoolTotals,
+    value: i64,
+    resolved_by: Pubkey,
+    now: i64,
+) -> Result<()> {
+    // Nobody to pay: void so every stake is refunded instead of the whole
+    // pool being left behind as dust
+    if resolution_voids_market(market, totals, value) {
+        transition_market(market, MarketStatus::Cancelled, now)?;
+        market.cancelled_at = Some(now);
+
+        emit!(MarketCancelled {
+            market: market.key(),
+            cancelled_by: resolved_by,
+        });
+
+        return Ok(());
+    }
+
+    transition_market(market, MarketStatus::Resolved, now)?;
+
+    record_pool_totals(market, totals);
+    match market.market_type {
+        MarketType::Scalar => market.scalar_value = Some(value),
+        _ => market.winning_outcome = Some(value as u8),
+    }
+    market.resolved_at = Some(now);
+    market.resolved_by = Some(resolved_by);
+
+    emit!(MarketResolved {
+        market: market.key(),
+        winning_outcome: market.winning_outcome,
+        scalar_value: market.scalar_value,
+        resolved_by,
+    });
+
+    Ok(())
+}
+
+/// A parimutuel market whose winning outcome has no stake behind it has no
+/// one to pay out to. LMSR winners are backed by the subsidy instead.
+fn resolution_voids_market(market: &Market, totals: &PoolTotals, value: i64) -> bool {
+    market.market_type == MarketType::Parimutuel && totals.outcome_stakes[value as usize] == 0
+}
+
+/// Categorical markets resolve to an outcome index; scalar markets accept
+/// any value and clamp it to [lower, upper] at payout time.
+fn validate_resolution_value(market: &Market, value: i64) -> Result<()> {
+    if market.market_type != MarketType::Scalar {
+        require!(
+            value >= 0 && value < market.num_outcomes as i64,
+            ErrorCode::InvalidOutcome
+        );
+    }
+    Ok(())
+}
+
+/// Committee is optional (empty with threshold 0). When present the
+/// threshold must be a strict majority so two outcomes can never both
+/// reach quorum.
+fn validate_committee(committee: &[Pubkey], threshold: u8) -> Result<()> {
+    if committee.is_empty() {
+        require!(threshold == 0, ErrorCode::InvalidResolverCommittee);
+        return Ok(());
+    }
+
+    require!(
+        committee.len() <= MAX_RESOLVERS,
+        ErrorCode::InvalidResolverCommittee
+    );
+    require!(
+        threshold as usize * 2 > committee.len() && threshold as usize <= committee.len(),
+        ErrorCode::InvalidResolverCommittee
+    );
+    for (i, key) in committee.iter().enumerate() {
+        require!(
+            !committee[i + 1..].contains(key),
+            ErrorCode::InvalidResolverCommittee
+        );
+    }
+
+    Ok(())
+}
+
+fn role_is_expired(role: &AdminRole) -> bool {
+    role.expires_at.map_or(false, |expires_at| {
+        Clock::get().map_or(true, |clock| clock.unix_timestamp >= expires_at)
+    })
+}
+
+fn role_is_active(role: &AdminRole) -> bool {
+    !role.revoked && !role_is_expired(role)
+}
+
+fn validate_fee_split(fees: &FeeSplit) -> Result<()> {
+    require!(
+        fees.settlement_fee_bps <= MAX_SETTLEMENT_FEE_BPS
+            && fees.platform_share_bps as u64 <= BPS_DENOMINATOR,
+        ErrorCode::InvalidFeeSchedule
+    );
+    Ok(())
+}
+
+fn fees_for(platform_config: &PlatformConfig, company: &Pubkey) -> FeeSplit {
+    platform_config
+        .fee_overrides
+        .iter()
+        .find(|o| o.company == *company)
+        .map_or(platform_config.default_fees, |o| o.fees)
+}
+
+/// Splits the settlement fee on a gross payout into (platform, company).
+/// Rounds down in the claimant's favour; fee never exceeds the payout.
+fn settlement_fees(fees: &FeeSplit, payout: u64) -> (u64, u64) {
+    let fee = (payout as u128 * fees.settlement_fee_bps as u128 / BPS_DENOMINATOR as u128) as u64;
+    let platform_fee =
+        (fee as u128 * fees.platform_share_bps as u128 / BPS_DENOMINATOR as u128) as u64;
+    (platform_fee, fee - platform_fee)
+}
+
+fn validate_fee_schedule(company_registration_fee: u64, admin_grant_fee: u64) -> Result<()> {
+    require!(
+        company_registration_fee <= MAX_COMPANY_REGISTRATION_FEE
+            && admin_grant_fee <= MAX_ADMIN_GRANT_FEE,
+        ErrorCode::InvalidFeeSchedule
+    );
+    Ok(())
+}
+
+/// Single check for every grant-like instruction. The company authority
+/// passes while the company is in single-key mode; anyone else needs their
+/// own active role with GrantRole, and can only hand out permission bits
+/// it holds itself, expiring no later than its own role does.
+fn require_can_grant(
+    company: &Account<Company>,
+    granter: &Pubkey,
+    granter_role: Option<&AdminRole>,
+    permissions: u16,
+    expires_at: Option<i64>,
+) -> Result<()> {
+    if company.signer_threshold == 0 && *granter == company.authority {
+        return Ok(());
+    }
+
+    let role = granter_role.ok_or(ErrorCode::Unauthorized)?;
+    require!(
+        role.user == *granter && role.company == company.key(),
+        ErrorCode::Unauthorized
+    );
+    require!(!role.revoked, ErrorCode::RoleRevoked);
+    require!(!role_is_expired(role), ErrorCode::RoleExpired);
+    require!(
+        role.permissions & PERM_GRANT_ROLE != 0 && permissions & !role.permissions == 0,
+        ErrorCode::MissingPermission
+    );
+    if let Some(limit) = role.expires_at {
+        require!(
+            expires_at.map_or(false, |expires_at| expires_at <= limit),
+            ErrorCode::ExpiryExceedsGranter
+        );
+    }
+
+    Ok(())
+}
+
+/// Company authority holds every permission; admins need the bit and an
+/// active (unrevoked, unexpired) role.
+fn has_permission(
+    company: &Company,
+    signer: &Pubkey,
+    role: &Option<Account<AdminRole>>,
+    permission: u16,
+) -> bool {
+    *signer == company.authority || role_grants(role, permission)
+}
+
+/// Like has_permission, but in threshold mode the authority key alone no
+/// longer qualifies; it must go through a CompanyProposal.
+fn has_sensitive_permission(
+    company: &Company,
+    signer: &Pubkey,
+    role: &Option<Account<AdminRole>>,
+    permission: u16,
+) -> bool {
+    (company.signer_threshold == 0 && *signer == company.authority)
+        || role_grants(role, permission)
+}
+
+fn require_not_paused(company: &Company, scope: u8) -> Result<()> {
+    require!(company.pause_flags & scope == 0, ErrorCode::CompanyPaused);
+    Ok(())
+}
+
+fn role_grants(role: &Option<Account<AdminRole>>, permission: u16) -> bool {
+    role.as_ref()
+        .map_or(false, |role| role_is_active(role) && role.permissions & permission != 0)
+}
+
+/// Signer set is optional (empty with threshold 0). Unlike resolver
+/// committees any threshold from 1 to the set size is allowed.
+fn validate_signer_set(signers: &[Pubkey], threshold: u8) -> Result<()> {
+    if signers.is_empty() {
+        require!(threshold == 0, ErrorCode::InvalidSignerSet);
+        return Ok(());
+    }
+
+    require!(
+        signers.len() <= MAX_COMPANY_SIGNERS,
+        ErrorCode::InvalidSignerSet
+    );
+    require!(
+        threshold > 0 && threshold as usize <= signers.len(),
+        ErrorCode::InvalidSignerSet
+    );
+    for (i, key) in signers.iter().enumerate() {
+        require!(
+            *key != Pubkey::default() && !signers[i + 1..].contains(key),
+            ErrorCode::InvalidSignerSet
+        );
+    }
+
+    Ok(())
+}
+
+fn signer_index(company: &Company, key: &Pubkey) -> Result<usize> {
+    company
+        .signers
+        .iter()
+        .position(|signer| signer == key)
+        .ok_or_else(|| error!(ErrorCode::NotCompanySigner))
+}
+
+fn validate_company_action(company: &Company, action: &CompanyAction) -> Result<()> {
+    match action {
+        CompanyAction::SetPendingAuthority {
+            pending_authority: Some(new_authority),
+        } => {
+            require!(
+                *new_authority != company.authority && *new_authority != Pubkey::default(),
+                ErrorCode::InvalidAuthority
+            );
+        }
+        CompanyAction::SetPendingAuthority {
+            pending_authority: None,
+        } => {
+            require!(
+                company.pending_authority.is_some(),
+                ErrorCode::NoPendingAuthority
+            );
+        }
+        CompanyAction::UpdateSignerSet { signers, threshold } => {
+            validate_signer_set(signers, *threshold)?;
+        }
+        CompanyAction::UpdateTimelockConfig { delay, guardians } => {
+            validate_timelock_config(*delay, guardians)?;
+        }
+        CompanyAction::SetPauseGuardians { guardians } => {
+            validate_guardians(guardians)?;
+        }
+        CompanyAction::UpdateResolutionConfig { dispute_window, .. } => {
+            require!(
+                (MIN_DISPUTE_WINDOW..=MAX_DISPUTE_WINDOW).contains(dispute_window),
+                ErrorCode::InvalidDisputeWindow
+            );
+        }
+        CompanyAction::GrantAdminRole { permissions, .. }
+        | CompanyAction::RegrantAdminRole { permissions, .. } => {
+            require!(
+                *permissions != 0 && *permissions & !PERM_ALL == 0,
+                ErrorCode::InvalidPermissions
+            );
+        }
+        _ => {}
+    }
+
+    Ok(())
+}
+
+fn validate_timelock_config(delay: i64, guardians: &[Pubkey]) -> Result<()> {
+    require!(
+        (0..=MAX_TIMELOCK_DELAY).contains(&delay),
+        ErrorCode::InvalidTimelockConfig
+    );
+    validate_guardians(guardians)
+}
+
+fn validate_guardians(guardians: &[Pubkey]) -> Result<()> {
+    require!(
+        guardians.len() <= MAX_GUARDIANS,
+        ErrorCode::InvalidGuardians
+    );
+    for (i, key) in guardians.iter().enumerate() {
+        require!(
+            *key != Pubkey::default() && !guardians[i + 1..].contains(key),
+            ErrorCode::InvalidGuardians
+        );
+    }
+
+    Ok(())
+}
+
+/// Threshold met, signer set unchanged and, for timelocked actions, the
+/// eta has passed.
+fn require_executable(company: &Company, proposal: &CompanyProposal) -> Result<()> {
+    require!(
+        proposal.signer_set_version == company.signer_set_version,
+        ErrorCode::StaleProposal
+    );
+    require!(
+        proposal.approval_count >= company.signer_threshold,
+        ErrorCode::InsufficientApprovals
+    );
+    if is_timelocked(&proposal.action) {
+        let eta = proposal.eta.ok_or(ErrorCode::TimelockNotElapsed)?;
+        require!(
+            Clock::get()?.unix_timestamp >= eta,
+            ErrorCode::TimelockNotElapsed
+        );
+    }
+    Ok(())
+}
+
+/// Root updates, revocations and the timelock itself wait out the delay.
+fn is_timelocked(action: &CompanyAction) -> bool {
+    matches!(
+        action,
+        CompanyAction::UpdateEmployeeMerkleRoot { .. }
+            | CompanyAction::RevokeAdminRole { .. }
+            | CompanyAction::UpdateTimelockConfig { .. }
+    )
 }
 
-#[derive(Accounts)]
-#[instruction(market_id: u64)]
-pub struct CreateMarket<'info> {
-    #[account(mut)]
-    pub company: Account<'info, Company>,
+/// Starts the delay the first time a timelocked proposal has enough
+/// approvals. The delay is fixed at this point.
+fn start_timelock_if_approved(
+    company: &Company,
+    proposal: &mut Account<CompanyProposal>,
+    now: i64,
+) -> Result<()> {
+    if proposal.eta.is_some()
+        || proposal.approval_count < company.signer_threshold
+        || !is_timelocked(&proposal.action)
+    {
+        return Ok(());
+    }
 
-    #[account(
-        init,
-        payer = admin,
-        space = 8 + 351,
-        seeds = [
-            b"market",
-            company.key().as_ref(),
-            market_id.to_le_bytes().as_ref()
-        ],
-        bump
-    )]
-    pub market: Account<'info, Market>,
+    let eta = now
+        .checked_add(company.timelock_delay)
+        .ok_or(ErrorCode::Overflow)?;
+    proposal.eta = Some(eta);
 
-    // Verify admin role
-    #[account(
-        seeds = [
-            b"admin_role",
-            company.key().as_ref(),
-            admin.key().as_ref()
-        ],
-        bump,
-        constraint = !admin_role.revoked @ ErrorCode::RoleRevoked
-    )]
-    pub admin_role: Account<'info, AdminRole>,
+    emit!(CompanyActionQueued {
+        company: proposal.company,
+        proposal: proposal.key(),
+        proposal_id: proposal.proposal_id,
+        eta,
+    });
 
-    #[account(mut)]
-    pub admin: Signer<'info>,
+    Ok(())
+}
 
-    // Rate limiting
-    #[account(
-        init_if_needed,
-        payer = admin,
-        space = 8 + 83,
-        seeds = [
-            b"rate_limit",
-            company.key().as_ref(),
-            admin.key().as_ref()
-        ],
-        bump
-    )]
-    pub rate_limit_state: Account<'info, RateLimitState>,
+// The apply_* helpers are shared by the direct instructions and
+// execute_company_proposal; callers have already authorized the change.
 
-    pub system_program: Program<'info, System>,
+fn apply_employee_root_update(company: &mut Account<Company>, new_root: [u8; 32]) -> Result<()> {
+    company.employee_merkle_root = new_root;
+    company.employee_root_version = company
+        .employee_root_version
+        .checked_add(1)
+        .ok_or(ErrorCode::Overflow)?;
+
+    emit!(EmployeeMerkleRootUpdated {
+        company: company.key(),
+        new_version: company.employee_root_version,
+    });
+
+    Ok(())
 }
 
-#[derive(Accounts)]
-pub struct ResolveMarket<'info> {
-    #[account(
-        mut,
-        constraint = market.company == company.key() @ ErrorCode::WrongCompany
-    )]
-    pub market: Account<'info, Market>,
+fn apply_unpause(
+    company: &mut Account<Company>,
+    flags: u8,
+    reason: u16,
+    unpaused_by: Pubkey,
+) -> Result<()> {
+    require!(
+        flags != 0 && flags & !PAUSE_ALL == 0,
+        ErrorCode::InvalidPauseFlags
+    );
+
+    company.pause_flags &= !flags;
+
+    emit!(CompanyUnpaused {
+        company: company.key(),
+        flags,
+        pause_flags: company.pause_flags,
+        reason,
+        unpaused_by,
+    });
+
+    Ok(())
+}
 
-    pub company: Account<'info, Company>,
+/// Marks the role revoked; closing the account is left to the caller.
+fn apply_admin_role_revocation(
+    company: &mut Account<Company>,
+    admin_role: &mut Account<AdminRole>,
+    revoked_by: Pubkey,
+    closed: bool,
+) -> Result<()> {
+    // A role revoked earlier without closing can still be closed to reclaim
+    // its rent; it already left admin_count, so only the event is repeated
+    if admin_role.revoked {
+        require!(closed, ErrorCode::AlreadyRevoked);
 
-    // Verify admin role
-    #[account(
-        seeds = [
-            b"admin_role",
-            company.key().as_ref(),
-            admin.key().as_ref()
-        ],
-        bump,
-        constraint = !admin_role.revoked @ ErrorCode::RoleRevoked
-    )]
-    pub admin_role: Account<'info, AdminRole>,
+        emit!(AdminRoleRevoked {
+            company: company.key(),
+            user: admin_role.user,
+            revoked_by,
+            closed,
+        });
 
-    pub admin: Signer<'info>,
+        return Ok(());
+    }
+
+    admin_role.revoked = true;
+    admin_role.revoked_at = Some(Clock::get()?.unix_timestamp);
+    admin_role.revoked_by = Some(revoked_by);
+
+    company.admin_count = company
+        .admin_count
+        .checked_sub(1)
+        .ok_or(ErrorCode::Underflow)?;
+
+    emit!(AdminRoleRevoked {
+        company: company.key(),
+        user: admin_role.user,
+        revoked_by,
+        closed,
+    });
+
+    Ok(())
 }
 
-#[derive(Accounts)]
-pub struct PlaceBet<'info> {
-    #[account(
-        constraint = market.company == company.key() @ ErrorCode::WrongCompany
-    )]
-    pub market: Account<'info, Market>,
+fn validate_role_terms(permissions: u16, expires_at: Option<i64>, now: i64) -> Result<()> {
+    require!(
+        permissions != 0 && permissions & !PERM_ALL == 0,
+        ErrorCode::InvalidPermissions
+    );
+    require!(
+        expires_at.map_or(true, |expires_at| expires_at > now),
+        ErrorCode::InvalidExpiry
+    );
+    Ok(())
+}
 
-    pub company: Account<'info, Company>,
+/// Fills in a freshly initialised role PDA.
+fn apply_admin_role_grant(
+    company: &mut Account<Company>,
+    admin_role: &mut Account<AdminRole>,
+    user: Pubkey,
+    granted_by: Pubkey,
+    permissions: u16,
+    expires_at: Option<i64>,
+    bump: u8,
+) -> Result<()> {
+    require!(
+        company.admin_count < MAX_ADMINS_PER_COMPANY,
+        ErrorCode::TooManyAdmins
+    );
+
+    admin_role.user = user;
+    admin_role.company = company.key();
+    admin_role.granted_at = Clock::get()?.unix_timestamp;
+    admin_role.granted_by = granted_by;
+    admin_role.permissions = permissions;
+    admin_role.expires_at = expires_at;
+    admin_role.revoked = false;
+    admin_role.bump = bump;
+
+    company.admin_count = company
+        .admin_count
+        .checked_add(1)
+        .ok_or(ErrorCode::Overflow)?;
+
+    emit!(AdminRoleGranted {
+        company: company.key(),
+        user,
+        granted_by,
+        permissions,
+        expires_at,
+    });
+
+    Ok(())
+}
 
-    #[account(
-        init,
-        payer = user,
-        space = 8 + 100,
-        seeds = [
-            b"bet",
-            market.key().as_ref(),
-            user.key().as_ref()
-        ],
-        bump
-    )]
-    pub bet: Account<'info, Bet>,
+fn apply_admin_role_regrant(
+    company: &mut Account<Company>,
+    admin_role: &mut Account<AdminRole>,
+    granted_by: Pubkey,
+    permissions: u16,
+    expires_at: Option<i64>,
+) -> Result<()> {
+    require!(admin_role.revoked, ErrorCode::NotRevoked);
+    require!(
+        company.admin_count < MAX_ADMINS_PER_COMPANY,
+        ErrorCode::TooManyAdmins
+    );
+
+    let previous_revoked_at = admin_role.revoked_at;
+    let previous_revoked_by = admin_role.revoked_by;
+
+    admin_role.granted_at = Clock::get()?.unix_timestamp;
+    admin_role.granted_by = granted_by;
+    admin_role.permissions = permissions;
+    admin_role.expires_at = expires_at;
+    admin_role.revoked = false;
+    admin_role.revoked_at = None;
+    admin_role.revoked_by = None;
+
+    company.admin_count = company
+        .admin_count
+        .checked_add(1)
+        .ok_or(ErrorCode::Overflow)?;
+
+    emit!(AdminRoleRegranted {
+        company: company.key(),
+        user: admin_role.user,
+        granted_by,
+        permissions,
+        expires_at,
+        previous_revoked_at,
+        previous_revoked_by,
+    });
+
+    Ok(())
+}
 
-    // Optional admin role check (if None, requires merkle proof)
-    #[account(
-        seeds = [
-            b"admin_role",
-            company.key().as_ref(),
-            user.key().as_ref()
-        ],
-        bump
-    )]
-    pub admin_role: Option<Account<'info, AdminRole>>,
+fn apply_admin_role_renewal(
+    admin_role: &mut Account<AdminRole>,
+    renewed_by: Pubkey,
+    expires_at: Option<i64>,
+) -> Result<()> {
+    require!(!admin_role.revoked, ErrorCode::RoleRevoked);
+    let now = Clock::get()?.unix_timestamp;
+    require!(
+        expires_at.map_or(true, |expires_at| expires_at > now),
+        ErrorCode::InvalidExpiry
+    );
+
+    admin_role.expires_at = expires_at;
+
+    emit!(AdminRoleRenewed {
+        company: admin_role.company,
+        user: admin_role.user,
+        renewed_by,
+        expires_at,
+    });
+
+    Ok(())
+}
 
-    #[account(mut)]
-    pub user: Signer<'info>,
+fn apply_resolution_config(
+    company: &mut Account<Company>,
+    dispute_window: i64,
+    resolution_bond: u64,
+) -> Result<()> {
+    require!(
+        (MIN_DISPUTE_WINDOW..=MAX_DISPUTE_WINDOW).contains(&dispute_window),
+        ErrorCode::InvalidDisputeWindow
+    );
+
+    company.dispute_window = dispute_window;
+    company.resolution_bond = resolution_bond;
+
+    emit!(ResolutionConfigUpdated {
+        company: company.key(),
+        dispute_window,
+        resolution_bond,
+    });
+
+    Ok(())
+}
 
-    pub system_program: Program<'info, System>,
+fn apply_pending_authority(
+    company: &mut Account<Company>,
+    pending_authority: Option<Pubkey>,
+) -> Result<()> {
+    validate_company_action(
+        company,
+        &CompanyAction::SetPendingAuthority { pending_authority },
+    )?;
+
+    match pending_authority {
+        Some(new_authority) => {
+            company.pending_authority = Some(new_authority);
+
+            emit!(AuthorityTransferProposed {
+                company: company.key(),
+                current_authority: company.authority,
+                pending_authority: new_authority,
+            });
+        }
+        None => {
+            let pending_authority = company
+                .pending_authority
+                .take()
+                .ok_or(ErrorCode::NoPendingAuthority)?;
+
+            emit!(AuthorityTransferCancelled {
+                company: company.key(),
+                pending_authority,
+            });
+        }
+    }
+
+    Ok(())
 }
 
-#[derive(Accounts)]
-pub struct ClaimWinnings<'info> {
-    #[account(
-        mut,
-        seeds = [
-            b"bet",
-            bet.market.as_ref(),
-            user.key().as_ref()
-        ],
-        bump = bet.bump,
-        constraint = bet.user == user.key() @ ErrorCode::Unauthorized
-    )]
-    pub bet: Account<'info, Bet>,
+fn apply_timelock_config(
+    company: &mut Account<Company>,
+    delay: i64,
+    guardians: Vec<Pubkey>,
+) -> Result<()> {
+    validate_timelock_config(delay, &guardians)?;
 
-    #[account(
-        constraint = market.key() == bet.market @ ErrorCode::WrongMarket
-    )]
-    pub market: Account<'info, Market>,
+    company.timelock_delay = delay;
+    company.guardians = guardians;
 
-    pub user: Signer<'info>,
+    emit!(TimelockConfigUpdated {
+        company: company.key(),
+        delay,
+        guardians: company.guardians.clone(),
+    });
+
+    Ok(())
+}
+
+fn apply_pause_guardians(company: &mut Account<Company>, guardians: Vec<Pubkey>) -> Result<()> {
+    validate_guardians(&guardians)?;
+
+    company.pause_guardians = guardians;
+
+    emit!(PauseGuardiansUpdated {
+        company: company.key(),
+        guardians: company.pause_guardians.clone(),
+    });
+
+    Ok(())
+}
+
+/// Bumping the version invalidates every outstanding proposal, since
+/// approval bits index into the old signer list.
+fn apply_signer_set(
+    company: &mut Account<Company>,
+    signers: Vec<Pubkey>,
+    threshold: u8,
+) -> Result<()> {
+    validate_signer_set(&signers, threshold)?;
+
+    company.signers = signers;
+    company.signer_threshold = threshold;
+    company.signer_set_version = company
+        .signer_set_version
+        .checked_add(1)
+        .ok_or(ErrorCode::Overflow)?;
+
+    emit!(SignerSetUpdated {
+        company: company.key(),
+        signers: company.signers.clone(),
+        threshold,
+        version: company.signer_set_version,
+    });
+
+    Ok(())
+}
+
+fn is_resolver(market: &Market, key: &Pubkey) -> bool {
+    market.resolver == *key || market.resolver_committee.contains(key)
+}
+
+fn verify_bettor(
+    company: &Company,
+    admin_role: &Option<Account<AdminRole>>,
+    user: &Pubkey,
+    employee_proof: Option<Vec<[u8; 32]>>,
+    employee_proof_version: Option<u64>,
+) -> Result<()> {
+    let is_admin = admin_role.as_ref().map_or(false, |role| role_is_active(role));
+    if is_admin {
+        return Ok(());
+    }
+
+    // Must provide valid employee merkle proof
+    let proof = employee_proof.ok_or(ErrorCode::ProofRequired)?;
+    let proof_version = employee_proof_version.ok_or(ErrorCode::ProofVersionRequired)?;
+
+    require!(
+        proof.len() <= MAX_MERKLE_PROOF_DEPTH,
+        ErrorCode::ProofTooDeep
+    );
+
+    require!(
+        proof_version == company.employee_root_version,
+        ErrorCode::StaleProof
+    );
+
+    let leaf = hash_leaf(user);
+    require!(
+        verify_merkle_proof(&proof, company.employee_merkle_root, leaf),
+        ErrorCode::NotAuthorized
+    );
+
+    Ok(())
+}
+
+fn transfer_to_vault<'info>(
+    from: AccountInfo<'info>,
+    vault: &Account<'info, TokenAccount>,
+    authority: AccountInfo<'info>,
+    token_program: &Program<'info, Token>,
+    amount: u64,
+) -> Result<()> {
+    token::transfer(
+        CpiContext::new(
+            token_program.to_account_info(),
+            Transfer {
+                from,
+                to: vault.to_account_info(),
+                authority,
+            },
+        ),
+        amount,
+    )
+}
+
+fn transfer_from_vault<'info>(
+    market: &Account<'info, Market>,
+    vault: &Account<'info, TokenAccount>,
+    to: AccountInfo<'info>,
+    token_program: &Program<'info, Token>,
+    amount: u64,
+) -> Result<()> {
+    // The market PDA is the vault authority
+    let company_key = market.company;
+    let market_id_bytes = market.market_id.to_le_bytes();
+    let market_seeds: &[&[u8]] = &[
+        b"market",
+        company_key.as_ref(),
+        market_id_bytes.as_ref(),
+        &[market.bump],
+    ];
+
+    token::transfer(
+        CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            Transfer {
+                from: vault.to_account_info(),
+                to,
+                authority: market.to_account_info(),
+            },
+            &[market_seeds],
+        ),
+        amount,
+    )
 }
 
 // ============================================================================
-// HELPER FUNCTIONS
+// LMSR FIXED-POINT MATH
 // ============================================================================
+//
+// All values are u128 scaled by FP_SCALE. Share quantities and `b` are in
+// token base units, so C(q) comes back as base units * FP_SCALE.
+
+/// e^(-x) for x >= 0: split x = k*ln2 + r, then 2^-k * e^-r.
+fn exp_neg_fp(x: u128) -> u128 {
+    let k = x / LN_2_FP;
+    if k >= 40 {
+        return 0; // < 1e-12, below fixed-point resolution
+    }
+    let r = x - k * LN_2_FP;
+
+    // Taylor series for e^r (r < ln2, all terms positive), then invert
+    let mut term = FP_SCALE;
+    let mut sum = FP_SCALE;
+    for n in 1..24u128 {
+        term = term * r / FP_SCALE / n;
+        if term == 0 {
+            break;
+        }
+        sum += term;
+    }
 
-fn hash_leaf(pubkey: &Pubkey) -> [u8; 32] {
-    keccak::hash(pubkey.as_ref()).to_bytes()
+    (FP_SCALE * FP_SCALE / sum) >> k
 }
 
-fn verify_merkle_proof(proof: &[[u8; 32]], root: [u8; 32], leaf: [u8; 32]) -> bool {
-    let mut computed_hash = leaf;
+/// ln(x) for x >= 1: split x = 2^k * m with m in [1, 2), then
+/// ln(m) = 2 * atanh((m - 1) / (m + 1)).
+fn ln_fp(x: u128) -> u128 {
+    let mut k = 0u128;
+    let mut m = x;
+    while m >= 2 * FP_SCALE {
+        m /= 2;
+        k += 1;
+    }
 
-    for proof_element in proof.iter() {
-        computed_hash = if computed_hash <= *proof_element {
-            keccak::hashv(&[&computed_hash, proof_element]).to_bytes()
-        } else {
-            keccak::hashv(&[proof_element, &computed_hash]).to_bytes()
-        };
+    let z = (m - FP_SCALE) * FP_SCALE / (m + FP_SCALE);
+    let z2 = z * z / FP_SCALE;
+    let mut term = z;
+    let mut sum = 0u128;
+    let mut n = 1u128;
+    while term > 0 {
+        sum += term / n;
+        term = term * z2 / FP_SCALE;
+        n += 2;
+    }
+
+    k * LN_2_FP + 2 * sum
+}
+
+/// LMSR cost C(q) = q_max + b * ln(sum(e^((q_i - q_max) / b))).
+/// Factoring out q_max keeps every exponent <= 0 so nothing overflows.
+fn lmsr_cost_fp(shares: &[u64], b: u64) -> Result<u128> {
+    require!(b > 0, ErrorCode::InvalidLiquidity);
+
+    let q_max = shares.iter().copied().max().unwrap_or(0);
+    let mut sum = 0u128;
+    for &q in shares {
+        sum += exp_neg_fp((q_max - q) as u128 * FP_SCALE / b as u128);
+    }
+
+    (b as u128)
+        .checked_mul(ln_fp(sum))
+        .and_then(|v| v.checked_add(q_max as u128 * FP_SCALE))
+        .ok_or(error!(ErrorCode::Overflow))
+}
+
+/// Implied probability of each outcome, p_i = e^(q_i/b) / sum(e^(q_j/b)).
+fn lmsr_prices(shares: &[u64], b: u64) -> Result<[u64; MAX_OUTCOMES]> {
+    require!(b > 0, ErrorCode::InvalidLiquidity);
+
+    let q_max = shares.iter().copied().max().unwrap_or(0);
+    let mut weights = [0u128; MAX_OUTCOMES];
+    let mut sum = 0u128;
+    for (i, &q) in shares.iter().enumerate() {
+        weights[i] = exp_neg_fp((q_max - q) as u128 * FP_SCALE / b as u128);
+        sum += weights[i];
+    }
+
+    let mut prices = [0u64; MAX_OUTCOMES];
+    for (i, w) in weights.iter().enumerate().take(shares.len()) {
+        prices[i] = (w * PRICE_SCALE as u128 / sum) as u64;
     }
 
-    computed_hash == root
+    Ok(prices)
+}
+
+/// Cost = C(q + amount * e_i) - C(q), rounded up in the vault's favour.
+/// Applies the trade to `shares`.
+fn lmsr_buy(shares: &mut [u64], b: u64, i: usize, amount: u64) -> Result<u64> {
+    let before = lmsr_cost_fp(shares, b)?;
+    shares[i] = shares[i].checked_add(amount).ok_or(ErrorCode::Overflow)?;
+    let after = lmsr_cost_fp(shares, b)?;
+    fp_to_units_ceil(after.checked_sub(before).ok_or(ErrorCode::Underflow)?)
+}
+
+/// Proceeds = C(q) - C(q - amount * e_i), rounded down. Applies the trade
+/// to `shares`.
+fn lmsr_sell(shares: &mut [u64], b: u64, i: usize, amount: u64) -> Result<u64> {
+    let before = lmsr_cost_fp(shares, b)?;
+    shares[i] = shares[i].checked_sub(amount).ok_or(ErrorCode::Underflow)?;
+    let after = lmsr_cost_fp(shares, b)?;
+    fp_to_units_floor(before.checked_sub(after).ok_or(ErrorCode::Underflow)?)
+}
+
+/// Worst-case market maker loss, b * ln(n), rounded up plus one unit of
+/// headroom for fixed-point error.
+fn lmsr_subsidy(b: u64, num_outcomes: u8) -> Result<u64> {
+    let loss_fp = (b as u128)
+        .checked_mul(ln_fp(num_outcomes as u128 * FP_SCALE))
+        .ok_or(ErrorCode::Overflow)?;
+
+    fp_to_units_ceil(loss_fp)?
+        .checked_add(1)
+        .ok_or(error!(ErrorCode::Overflow))
+}
+
+fn fp_to_units_ceil(value: u128) -> Result<u64> {
+    u64::try_from((value + FP_SCALE - 1) / FP_SCALE).map_err(|_| error!(ErrorCode::Overflow))
+}
+
+fn fp_to_units_floor(value: u128) -> Result<u64> {
+    u64::try_from(value / FP_SCALE).map_err(|_| error!(ErrorCode::Overflow))
+}
+
+// ============================================================================
+// EVENTS
+// ============================================================================
+
+#[event]
+pub struct PlatformConfigUpdated {
+    pub admin: Pubkey,
+    pub treasury: Pubkey,
+    pub company_registration_fee: u64,
+    pub admin_grant_fee: u64,
+}
+
+#[event]
+pub struct PlatformPaused {
+    pub paused: bool,
+    pub admin: Pubkey,
+}
+
+#[event]
+pub struct PlatformAdminProposed {
+    pub admin: Pubkey,
+    pub pending_admin: Option<Pubkey>,
+}
+
+#[event]
+pub struct PlatformAdminTransferred {
+    pub previous_admin: Pubkey,
+    pub new_admin: Pubkey,
+}
+
+#[event]
+pub struct CompanyRegistered {
+    pub company: Pubkey,
+    pub company_id: u64,
+    pub authority: Pubkey,
+}
+
+#[event]
+pub struct EmployeeMerkleRootUpdated {
+    pub company: Pubkey,
+    pub new_version: u64,
+}
+
+#[event]
+pub struct CompanyPaused {
+    pub company: Pubkey,
+    pub flags: u8,       // Flags set by this call
+    pub pause_flags: u8, // Resulting company.pause_flags
+    pub reason: u16,     // Off-chain incident/reason code
+    pub paused_by: Pubkey,
+}
+
+#[event]
+pub struct CompanyUnpaused {
+    pub company: Pubkey,
+    pub flags: u8,
+    pub pause_flags: u8,
+    pub reason: u16,
+    pub unpaused_by: Pubkey,
+}
+
+#[event]
+pub struct ResolutionConfigUpdated {
+    pub company: Pubkey,
+    pub dispute_window: i64,
+    pub resolution_bond: u64,
+}
+
+#[event]
+pub struct AuthorityTransferProposed {
+    pub company: Pubkey,
+    pub current_authority: Pubkey,
+    pub pending_authority: Pubkey,
+}
+
+#[event]
+pub struct AuthorityTransferred {
+    pub company: Pubkey,
+    pub previous_authority: Pubkey,
+    pub new_authority: Pubkey,
+}
+
+#[event]
+pub struct AuthorityTransferCancelled {
+    pub company: Pubkey,
+    pub pending_authority: Pubkey,
+}
+
+#[event]
+pub struct SignerSetUpdated {
+    pub company: Pubkey,
+    pub signers: Vec<Pubkey>,
+    pub threshold: u8,
+    pub version: u64,
+}
+
+#[event]
+pub struct CompanyProposalCreated {
+    pub company: Pubkey,
+    pub proposal: Pubkey,
+    pub proposal_id: u64,
+    pub proposer: Pubkey,
 }
 
-// ============================================================================
-// EVENTS
-// ============================================================================
+#[event]
+pub struct CompanyProposalApproved {
+    pub company: Pubkey,
+    pub proposal: Pubkey,
+    pub approver: Pubkey,
+    pub approval_count: u8,
+}
 
 #[event]
-pub struct CompanyRegistered {
+pub struct CompanyProposalExecuted {
     pub company: Pubkey,
-    pub company_id: u64,
-    pub authority: Pubkey,
+    pub proposal: Pubkey,
+    pub proposal_id: u64,
+    pub executed_by: Pubkey,
 }
 
 #[event]
-pub struct EmployeeMerkleRootUpdated {
+pub struct TimelockConfigUpdated {
     pub company: Pubkey,
-    pub new_version: u64,
+    pub delay: i64,
+    pub guardians: Vec<Pubkey>,
 }
 
 #[event]
-pub struct CompanyPaused {
+pub struct PauseGuardiansUpdated {
     pub company: Pubkey,
-    pub paused: bool,
+    pub guardians: Vec<Pubkey>,
+}
+
+#[event]
+pub struct CompanyActionQueued {
+    pub company: Pubkey,
+    pub proposal: Pubkey,
+    pub proposal_id: u64,
+    pub eta: i64,
+}
+
+#[event]
+pub struct CompanyActionCancelled {
+    pub company: Pubkey,
+    pub proposal: Pubkey,
+    pub proposal_id: u64,
+    pub cancelled_by: Pubkey,
 }
 
 #[event]
@@ -762,6 +5648,8 @@ pub struct AdminRoleGranted {
     pub company: Pubkey,
     pub user: Pubkey,
     pub granted_by: Pubkey,
+    pub permissions: u16,
+    pub expires_at: Option<i64>,
 }
 
 #[event]
@@ -769,6 +5657,32 @@ pub struct AdminRoleRevoked {
     pub company: Pubkey,
     pub user: Pubkey,
     pub revoked_by: Pubkey,
+    pub closed: bool,
+}
+
+#[event]
+pub struct AdminRoleRegranted {
+    pub company: Pubkey,
+    pub user: Pubkey,
+    pub granted_by: Pubkey,
+    pub permissions: u16,
+    pub expires_at: Option<i64>,
+    pub previous_revoked_at: Option<i64>,
+    pub previous_revoked_by: Option<Pubkey>,
+}
+
+#[event]
+pub struct AdminRoleRenewed {
+    pub company: Pubkey,
+    pub user: Pubkey,
+    pub renewed_by: Pubkey,
+    pub expires_at: Option<i64>,
+}
+
+#[event]
+pub struct AdminRoleExpired {
+    pub company: Pubkey,
+    pub user: Pubkey,
 }
 
 #[event]
@@ -779,13 +5693,70 @@ pub struct MarketCreated {
     pub creator: Pubkey,
 }
 
+#[event]
+pub struct MarketMetadataUpdated {
+    pub market: Pubkey,
+    pub metadata_uri: String,
+    pub metadata_hash: [u8; 32],
+}
+
+#[event]
+pub struct MarketOpened {
+    pub market: Pubkey,
+    pub close_time: i64,
+}
+
+#[event]
+pub struct OutcomeProposed {
+    pub market: Pubkey,
+    pub value: i64,
+    pub proposer: Pubkey,
+    pub dispute_deadline: i64,
+    pub bond: u64,
+}
+
+#[event]
+pub struct ResolutionVoteCast {
+    pub market: Pubkey,
+    pub resolver: Pubkey,
+    pub outcome: u8,
+}
+
+#[event]
+pub struct OutcomeDisputed {
+    pub market: Pubkey,
+    pub challenger: Pubkey,
+    pub bond: u64,
+}
+
+#[event]
+pub struct DisputeArbitrated {
+    pub market: Pubkey,
+    pub ruling: Option<i64>,
+    pub arbitrator: Pubkey,
+}
+
 #[event]
 pub struct MarketResolved {
     pub market: Pubkey,
-    pub winning_outcome: u8,
+    pub winning_outcome: Option<u8>,
+    pub scalar_value: Option<i64>,
     pub resolved_by: Pubkey,
 }
 
+#[event]
+pub struct MarketTotalsSynced {
+    pub market: Pubkey,
+    pub total_volume: u64,
+    pub outcome_stakes: [u64; MAX_OUTCOMES],
+}
+
+#[event]
+pub struct MarketCancelled {
+    pub market: Pubkey,
+    pub cancelled_by: Pubkey,
+}
+
 #[event]
 pub struct BetPlaced {
     pub bet: Pubkey,
@@ -799,7 +5770,70 @@ pub struct BetPlaced {
 pub struct WinningsClaimed {
     pub bet: Pubkey,
     pub user: Pubkey,
-    pub payout: u64,
+    pub payout: u64, // Net of the settlement fee
+    pub fee: u64,
+}
+
+#[event]
+pub struct FeeScheduleUpdated {
+    pub company: Option<Pubkey>, // None for the platform default
+    pub fees: Option<FeeSplit>,  // None when an override is removed
+}
+
+#[event]
+pub struct PlatformFeesClaimed {
+    pub market: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct CompanyFeesClaimed {
+    pub company: Pubkey,
+    pub market: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct CompanyTreasuryWithdrawn {
+    pub company: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub destination: Pubkey,
+    pub withdrawn_by: Pubkey,
+}
+
+#[event]
+pub struct BetWithdrawn {
+    pub bet: Pubkey,
+    pub market: Pubkey,
+    pub user: Pubkey,
+    pub outcome: u8,
+    pub amount: u64,
+    pub fee: u64,
+}
+
+#[event]
+pub struct SharesTraded {
+    pub market: Pubkey,
+    pub user: Pubkey,
+    pub outcome: u8,
+    pub shares: u64,
+    pub amount: u64,
+    pub is_buy: bool,
+    pub implied_probs: [u64; MAX_OUTCOMES],
+}
+
+#[event]
+pub struct BetRefunded {
+    pub bet: Pubkey,
+    pub user: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct DustSwept {
+    pub market: Pubkey,
+    pub amount: u64,
 }
 
 // ============================================================================
@@ -820,9 +5854,84 @@ pub enum ErrorCode {
     #[msg("Role already revoked")]
     AlreadyRevoked,
 
+    #[msg("Role is not revoked")]
+    NotRevoked,
+
+    #[msg("Invalid authority: must differ from the current authority")]
+    InvalidAuthority,
+
+    #[msg("No authority transfer is pending")]
+    NoPendingAuthority,
+
+    #[msg("Signer is not the pending authority")]
+    NotPendingAuthority,
+
+    #[msg("Company uses threshold authority: submit this action as a proposal")]
+    ProposalRequired,
+
+    #[msg("Company has no signer set configured")]
+    NoSignerSet,
+
+    #[msg("Invalid signer set: unique keys, at most 5, threshold between 1 and set size")]
+    InvalidSignerSet,
+
+    #[msg("Signer is not in the company signer set")]
+    NotCompanySigner,
+
+    #[msg("Signer has already approved this proposal")]
+    AlreadyApproved,
+
+    #[msg("Proposal has not reached the approval threshold")]
+    InsufficientApprovals,
+
+    #[msg("Proposal was created under a previous signer set")]
+    StaleProposal,
+
+    #[msg("Admin role account does not match the proposal")]
+    AdminRoleMismatch,
+
+    #[msg("Company has a timelock: queue this action instead")]
+    TimelockRequired,
+
+    #[msg("Timelock delay has not elapsed")]
+    TimelockNotElapsed,
+
+    #[msg("Only root updates, revocations and timelock changes can be queued")]
+    NotTimelocked,
+
+    #[msg("Invalid timelock config: delay up to 30 days, plus a valid guardian list")]
+    InvalidTimelockConfig,
+
+    #[msg("Guardian lists hold at most 3 unique, non-default keys")]
+    InvalidGuardians,
+
     #[msg("Role has been revoked")]
     RoleRevoked,
 
+    #[msg("Admin role lacks the permission required for this action")]
+    MissingPermission,
+
+    #[msg("Invalid permission bitmask")]
+    InvalidPermissions,
+
+    #[msg("Role has expired")]
+    RoleExpired,
+
+    #[msg("Role has not expired")]
+    RoleNotExpired,
+
+    #[msg("Invalid role expiry (must be in future)")]
+    InvalidExpiry,
+
+    #[msg("Granted expiry cannot outlive the granter's own role")]
+    ExpiryExceedsGranter,
+
+    #[msg("Admins cannot renew their own role")]
+    SelfRenewal,
+
+    #[msg("Grant proposals execute through execute_grant_proposal")]
+    WrongExecutionPath,
+
     #[msg("Company name too long (max 32 characters)")]
     NameTooLong,
 
@@ -832,20 +5941,26 @@ pub enum ErrorCode {
     #[msg("Market description too long (max 128 characters)")]
     DescriptionTooLong,
 
+    #[msg("Metadata URI too long (max 200 characters)")]
+    MetadataUriTooLong,
+
+    #[msg("Market metadata can only change while in Draft")]
+    MarketNotDraft,
+
     #[msg("Too many admins for this company (max 100)")]
     TooManyAdmins,
 
     #[msg("Invalid bet amount (must be > 0)")]
     InvalidAmount,
 
-    #[msg("Market already resolved")]
-    MarketResolved,
-
     #[msg("Market not resolved yet")]
     NotResolved,
 
-    #[msg("Market already resolved")]
-    AlreadyResolved,
+    #[msg("Market is not cancelled")]
+    NotCancelled,
+
+    #[msg("Illegal market status transition")]
+    InvalidStateTransition,
 
     #[msg("Bet already claimed")]
     AlreadyClaimed,
@@ -853,19 +5968,88 @@ pub enum ErrorCode {
     #[msg("Losing bet cannot be claimed")]
     LosingBet,
 
-    #[msg("Invalid number of outcomes (must be >= 2)")]
+    #[msg("Winning stakes have not all been claimed")]
+    UnclaimedWinnings,
+
+    #[msg("Instruction not supported for this market type")]
+    WrongMarketType,
+
+    #[msg("Invalid LMSR liquidity parameter (must be > 0)")]
+    InvalidLiquidity,
+
+    #[msg("Creator token account required to fund LMSR liquidity")]
+    FundingAccountRequired,
+
+    #[msg("Trade price moved beyond slippage limit")]
+    SlippageExceeded,
+
+    #[msg("Position too small for this sale")]
+    InsufficientPosition,
+
+    #[msg("Early exit fee too high (max 20%)")]
+    FeeTooHigh,
+
+    #[msg("Invalid number of outcomes (must be 2-8)")]
     InvalidOutcomes,
 
+    #[msg("Outcome labels must match outcome count (1-32 characters each)")]
+    InvalidOutcomeLabels,
+
+    #[msg("Pass every outcome ledger of the market, in outcome order")]
+    InvalidOutcomeLedgers,
+
+    #[msg("Invalid scalar range (lower must be below upper)")]
+    InvalidScalarRange,
+
     #[msg("Invalid outcome selected")]
     InvalidOutcome,
 
-    #[msg("Invalid resolution time (must be in future)")]
+    #[msg("Invalid close time (must be in future)")]
+    InvalidCloseTime,
+
+    #[msg("Invalid resolution time (must not precede close time)")]
     InvalidResolutionTime,
 
     #[msg("Too early to resolve market")]
     TooEarlyToResolve,
 
-    #[msg("Betting period has closed")]
+    #[msg("Conflict of interest: resolvers cannot hold positions in markets they resolve")]
+    ConflictOfInterest,
+
+    #[msg("Only the market's designated resolver can propose an outcome")]
+    NotDesignatedResolver,
+
+    #[msg("Instruction does not match this market's resolution mode")]
+    WrongResolutionMode,
+
+    #[msg("Invalid resolver committee (max 5 unique members, majority threshold)")]
+    InvalidResolverCommittee,
+
+    #[msg("Ed25519 oracle signature instruction missing")]
+    MissingOracleSignature,
+
+    #[msg("Oracle signature does not match the market's oracle")]
+    InvalidOracleSignature,
+
+    #[msg("Oracle report does not match market, outcome or timestamp")]
+    InvalidOracleReport,
+
+    #[msg("Dispute window has closed")]
+    DisputeWindowClosed,
+
+    #[msg("Dispute window is still open")]
+    DisputeWindowOpen,
+
+    #[msg("Market outcome is not under dispute")]
+    NotDisputed,
+
+    #[msg("Market has a proposed outcome; settle or void it through the dispute flow")]
+    ResolutionInProgress,
+
+    #[msg("Invalid dispute window (must be 1 hour to 7 days)")]
+    InvalidDisputeWindow,
+
+    #[msg("Market is not open for betting")]
     BettingClosed,
 
     #[msg("Wrong company for this market")]
@@ -874,9 +6058,33 @@ pub enum ErrorCode {
     #[msg("Wrong market for this bet")]
     WrongMarket,
 
+    #[msg("Token account mint does not match market mint")]
+    WrongMint,
+
+    #[msg("Platform is paused")]
+    PlatformPaused,
+
+    #[msg("Treasury does not match platform config")]
+    WrongTreasury,
+
+    #[msg("Fee exceeds the platform maximum")]
+    InvalidFeeSchedule,
+
+    #[msg("Too many company fee overrides")]
+    TooManyFeeOverrides,
+
+    #[msg("Company has no fee override")]
+    NoFeeOverride,
+
+    #[msg("No fees owed")]
+    NoFeesOwed,
+
     #[msg("Company is paused")]
     CompanyPaused,
 
+    #[msg("Invalid pause flags")]
+    InvalidPauseFlags,
+
     #[msg("Employee merkle proof required")]
     ProofRequired,
 
@@ -898,3 +6106,467 @@ pub enum ErrorCode {
     #[msg("Arithmetic underflow")]
     Underflow,
 }
+
+// ============================================================================
+// TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn empty_ledgers() -> Vec<OutcomeLedger> {
+        let market = Pubkey::new_unique();
+        (0..MAX_OUTCOMES as u8)
+            .map(|outcome| OutcomeLedger {
+                market,
+                outcome,
+                stake: 0,
+                bettors: 0,
+                volume: 0,
+                positions_opened: 0,
+                positions_closed: 0,
+                basis_in: 0,
+                basis_out: 0,
+                bump: 0,
+            })
+            .collect()
+    }
+
+    fn market(market_type: MarketType) -> Market {
+        Market {
+            company: Pubkey::new_unique(),
+            market_id: 0,
+            creator: Pubkey::new_unique(),
+            title: String::new(),
+            description: String::new(),
+            metadata_uri: String::new(),
+            metadata_hash: [0; 32],
+            created_at: 0,
+            close_time: 0,
+            resolution_time: 0,
+            num_outcomes: if market_type == MarketType::Scalar { 2 } else { MAX_OUTCOMES as u8 },
+            resolver: Pubkey::new_unique(),
+            resolver_committee: Vec::new(),
+            resolution_threshold: 0,
+            vote_counts: [0; MAX_OUTCOMES],
+            votes_cast: 0,
+            oracle: None,
+            status: MarketStatus::Resolved,
+            winning_outcome: None,
+            scalar_value: None,
+            scalar_lower: 0,
+            scalar_upper: 0,
+            resolved_at: None,
+            resolved_by: None,
+            proposed_value: None,
+            proposer: None,
+            challenger: None,
+            dispute_deadline: 0,
+            bond_amount: 0,
+            total_volume: 0,
+            outcome_stakes: [0; MAX_OUTCOMES],
+            claimed_stake: 0,
+            total_paid_out: 0,
+            cancelled_at: None,
+            mint: Pubkey::new_unique(),
+            vault: Pubkey::new_unique(),
+            market_type,
+            early_exit_fee_bps: 0,
+            fees: FeeSplit {
+                settlement_fee_bps: 0,
+                platform_share_bps: 0,
+            },
+            platform_fees_owed: 0,
+            company_fees_owed: 0,
+            liquidity_b: 0,
+            lmsr_shares: [0; MAX_OUTCOMES],
+            implied_probs: [0; MAX_OUTCOMES],
+            bump: 0,
+            vault_bump: 0,
+            bond_vault_bump: 0,
+        }
+    }
+
+    /// Deterministic xorshift so property tests need no extra dependencies.
+    struct Rng(u64);
+
+    impl Rng {
+        fn next(&mut self) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0
+        }
+
+        fn below(&mut self, n: u64) -> u64 {
+            self.next() % n
+        }
+    }
+
+    /// Places `bets` through open_stake and records the totals the way
+    /// settle_market does.
+    fn settle(market: &mut Market, bets: &mut [Bet], stakes: &[(usize, u64)]) -> PoolTotals {
+        let mut ledgers = empty_ledgers();
+        for (bet, &(outcome, amount)) in bets.iter_mut().zip(stakes) {
+            let ledger = &mut ledgers[outcome];
+            open_stake(ledger, bet, outcome, amount, amount).unwrap();
+            ledger.stake += amount;
+            ledger.volume += amount;
+        }
+        let totals = pool_totals(&ledgers).unwrap();
+        record_pool_totals(market, &totals);
+        totals
+    }
+
+    fn empty_bet() -> Bet {
+        Bet {
+            market: Pubkey::new_unique(),
+            user: Pubkey::new_unique(),
+            amount: 0,
+            outcome_amounts: [0; MAX_OUTCOMES],
+            placed_at: 0,
+            claimed: false,
+            claimed_at: None,
+            bump: 0,
+        }
+    }
+
+    #[test]
+    fn withdraw_then_rebet_keeps_bettor_counts_balanced() {
+        let mut ledgers = empty_ledgers();
+        let mut a = empty_bet();
+        let mut b = empty_bet();
+
+        open_stake(&mut ledgers[0], &mut a, 0, 100, 100).unwrap();
+        open_stake(&mut ledgers[0], &mut b, 0, 50, 50).unwrap();
+        close_stake(&mut ledgers[0], &mut a, 0, 100, 100).unwrap();
+        open_stake(&mut ledgers[1], &mut a, 1, 30, 30).unwrap();
+        let totals = pool_totals(&ledgers).unwrap();
+        assert_eq!(totals.total_bettors, 2);
+        assert_eq!(totals.bettor_counts[..2], [1, 1]);
+
+        close_stake(&mut ledgers[1], &mut a, 1, 30, 30).unwrap();
+        // Used to underflow: B must still be able to exit fully
+        close_stake(&mut ledgers[0], &mut b, 0, 50, 50).unwrap();
+
+        let totals = pool_totals(&ledgers).unwrap();
+        assert_eq!(totals.total_bettors, 0);
+        assert_eq!(totals.bettor_counts, [0; MAX_OUTCOMES]);
+        assert_eq!(totals.open_interest, 0);
+    }
+
+    #[test]
+    fn hedged_position_counts_once_per_outcome() {
+        let mut ledgers = empty_ledgers();
+        let mut bet = empty_bet();
+
+        open_stake(&mut ledgers[0], &mut bet, 0, 10, 10).unwrap();
+        open_stake(&mut ledgers[1], &mut bet, 1, 10, 10).unwrap();
+        open_stake(&mut ledgers[1], &mut bet, 1, 10, 10).unwrap();
+        let totals = pool_totals(&ledgers).unwrap();
+        assert_eq!(totals.total_bettors, 1);
+        assert_eq!(totals.bettor_counts[..2], [1, 1]);
+
+        close_stake(&mut ledgers[0], &mut bet, 0, 10, 10).unwrap();
+        let totals = pool_totals(&ledgers).unwrap();
+        assert_eq!(totals.total_bettors, 1);
+        assert_eq!(totals.bettor_counts[..2], [0, 1]);
+    }
+
+    #[test]
+    fn closing_more_than_held_is_rejected() {
+        let mut ledgers = empty_ledgers();
+        let mut bet = empty_bet();
+
+        open_stake(&mut ledgers[0], &mut bet, 0, 10, 10).unwrap();
+        assert!(close_stake(&mut ledgers[0], &mut bet, 0, 11, 10).is_err());
+    }
+
+    #[test]
+    fn parimutuel_payouts_never_exceed_pool() {
+        let mut rng = Rng(0x9e37_79b9_7f4a_7c15);
+        for _ in 0..500 {
+            let count = 1 + rng.below(50) as usize;
+            let stakes: Vec<(usize, u64)> = (0..count)
+                .map(|_| (rng.below(MAX_OUTCOMES as u64) as usize, 1 + rng.below(1 << 40)))
+                .collect();
+            let mut bets: Vec<Bet> = (0..count).map(|_| empty_bet()).collect();
+            let mut m = market(MarketType::Parimutuel);
+            settle(&mut m, &mut bets, &stakes);
+            let winner = rng.below(MAX_OUTCOMES as u64) as usize;
+            m.winning_outcome = Some(winner as u8);
+
+            let mut paid = 0u64;
+            let mut settled = 0u64;
+            let mut winners = 0u64;
+            for bet in &bets {
+                let (payout, stake) = position_payout(&m, bet).unwrap();
+                if stake > 0 {
+                    winners += 1;
+                }
+                paid += payout;
+                settled += stake;
+            }
+
+            assert!(paid <= m.total_volume);
+            assert_eq!(settled, winning_stake_total(&m).unwrap());
+            if winners > 0 {
+                // Each floor() loses strictly less than one base unit
+                assert!(m.total_volume - paid < winners);
+            }
+        }
+    }
+
+    #[test]
+    fn parimutuel_payout_is_pro_rata() {
+        assert_eq!(parimutuel_payout(1, 3, 7).unwrap(), 2);
+        assert_eq!(parimutuel_payout(2, 3, 7).unwrap(), 4);
+        assert_eq!(parimutuel_payout(u64::MAX, u64::MAX, u64::MAX).unwrap(), u64::MAX);
+        assert!(parimutuel_payout(1, 0, 7).is_err());
+        assert!(parimutuel_payout(4, 3, 7).is_err());
+    }
+
+    #[test]
+    fn position_payout_only_counts_the_winning_outcome() {
+        let mut m = market(MarketType::Parimutuel);
+        let mut bets = [empty_bet(), empty_bet()];
+        settle(&mut m, &mut bets, &[(0, 30), (1, 70)]);
+        bets[0].outcome_amounts[1] += 10;
+        bets[0].amount += 10;
+        m.outcome_stakes[1] += 10;
+        m.total_volume += 10;
+        m.winning_outcome = Some(0);
+
+        // Hedged bettor: 30 on the winner out of a 110 pool, loses the 10
+        assert_eq!(position_payout(&m, &bets[0]).unwrap(), (110, 30));
+        assert_eq!(position_payout(&m, &bets[1]).unwrap(), (0, 0));
+    }
+
+    #[test]
+    fn unbacked_winner_voids_parimutuel_markets_only() {
+        let mut m = market(MarketType::Parimutuel);
+        let mut bets = [empty_bet()];
+        let totals = settle(&mut m, &mut bets, &[(1, 100)]);
+        assert!(resolution_voids_market(&m, &totals, 0));
+        assert!(!resolution_voids_market(&m, &totals, 1));
+
+        m.market_type = MarketType::Lmsr;
+        assert!(!resolution_voids_market(&m, &totals, 0));
+    }
+
+    fn scalar_market(lower: i64, upper: i64, value: i64) -> Market {
+        let mut m = market(MarketType::Scalar);
+        m.scalar_lower = lower;
+        m.scalar_upper = upper;
+        m.scalar_value = Some(value);
+        m
+    }
+
+    #[test]
+    fn scalar_pools_split_linearly_and_round_down() {
+        let mut m = scalar_market(0, 3, 1);
+        let mut bets = [empty_bet(), empty_bet()];
+        settle(&mut m, &mut bets, &[(SCALAR_SHORT, 50), (SCALAR_LONG, 50)]);
+
+        assert_eq!(scalar_side_pools(&m).unwrap(), [66, 33]);
+        assert_eq!(position_payout(&m, &bets[0]).unwrap(), (66, 50));
+        assert_eq!(position_payout(&m, &bets[1]).unwrap(), (33, 50));
+        assert_eq!(winning_stake_total(&m).unwrap(), 100);
+    }
+
+    #[test]
+    fn scalar_value_is_clamped_to_range() {
+        let mut m = scalar_market(-10, 10, 1_000);
+        let mut bets = [empty_bet(), empty_bet()];
+        settle(&mut m, &mut bets, &[(SCALAR_SHORT, 40), (SCALAR_LONG, 60)]);
+
+        assert_eq!(position_payout(&m, &bets[0]).unwrap(), (0, 0));
+        assert_eq!(position_payout(&m, &bets[1]).unwrap(), (100, 60));
+        assert_eq!(winning_stake_total(&m).unwrap(), 60);
+    }
+
+    #[test]
+    fn one_sided_scalar_market_pays_the_staked_side_everything() {
+        for (staked, value) in [(SCALAR_LONG, 0), (SCALAR_LONG, 7), (SCALAR_SHORT, 10)] {
+            let mut m = scalar_market(0, 10, value);
+            let mut bets = [empty_bet(), empty_bet()];
+            settle(&mut m, &mut bets, &[(staked, 30), (staked, 70)]);
+
+            assert_eq!(position_payout(&m, &bets[0]).unwrap(), (30, 30));
+            assert_eq!(position_payout(&m, &bets[1]).unwrap(), (70, 70));
+            assert_eq!(winning_stake_total(&m).unwrap(), 100);
+        }
+    }
+
+    #[test]
+    fn scalar_pools_survive_the_full_i64_range() {
+        let mut m = scalar_market(i64::MIN, i64::MAX, i64::MAX - 1);
+        let mut bets = [empty_bet(), empty_bet()];
+        let half = u64::MAX / 2;
+        settle(&mut m, &mut bets, &[(SCALAR_SHORT, half), (SCALAR_LONG, half)]);
+
+        let [short, long] = scalar_side_pools(&m).unwrap();
+        assert!(short + long <= m.total_volume);
+        assert!(long > m.total_volume - 2 && short == 0);
+    }
+
+    #[test]
+    fn scalar_payouts_never_exceed_pool() {
+        let mut rng = Rng(0xdead_beef_cafe_f00d);
+        for _ in 0..500 {
+            let count = 1 + rng.below(30) as usize;
+            let stakes: Vec<(usize, u64)> = (0..count)
+                .map(|_| (rng.below(2) as usize, 1 + rng.below(1 << 40)))
+                .collect();
+            let mut bets: Vec<Bet> = (0..count).map(|_| empty_bet()).collect();
+            let mut m = if rng.below(2) == 0 {
+                scalar_market(0, 1_000, rng.below(1_200) as i64 - 100)
+            } else {
+                scalar_market(i64::MIN, i64::MAX, rng.next() as i64)
+            };
+            settle(&mut m, &mut bets, &stakes);
+
+            let mut paid = 0u64;
+            let mut settled = 0u64;
+            for bet in &bets {
+                let (payout, stake) = position_payout(&m, bet).unwrap();
+                paid += payout;
+                settled += stake;
+            }
+
+            assert!(paid <= m.total_volume);
+            assert_eq!(settled, winning_stake_total(&m).unwrap());
+            // Under one unit per side pool plus one per claim
+            assert!(m.total_volume - paid < count as u64 + 2);
+        }
+    }
+
+    fn assert_close(actual: u128, expected: u128, tolerance: u128) {
+        let diff = actual.max(expected) - actual.min(expected);
+        assert!(diff <= tolerance, "{actual} vs {expected}");
+    }
+
+    #[test]
+    fn exp_neg_fp_matches_known_values() {
+        assert_eq!(exp_neg_fp(0), FP_SCALE);
+        assert_close(exp_neg_fp(FP_SCALE / 2), 606_530_659_713, 2); // e^-0.5
+        assert_close(exp_neg_fp(FP_SCALE), 367_879_441_171, 2); // e^-1
+        assert_close(exp_neg_fp(10 * FP_SCALE), 45_399_930, 2); // e^-10
+        assert_eq!(exp_neg_fp(30 * FP_SCALE), 0); // below resolution
+    }
+
+    #[test]
+    fn ln_fp_matches_known_values() {
+        assert_eq!(ln_fp(FP_SCALE), 0);
+        assert_eq!(ln_fp(2 * FP_SCALE), LN_2_FP);
+        assert_close(ln_fp(3 * FP_SCALE / 2), 405_465_108_108, 16); // ln 1.5
+        assert_close(ln_fp(10 * FP_SCALE), 2_302_585_092_994, 16); // ln 10
+        assert_close(ln_fp(1_000 * FP_SCALE), 6_907_755_278_982, 16); // ln 1000
+    }
+
+    #[test]
+    fn lmsr_subsidy_covers_worst_case_loss() {
+        for n in 2..=MAX_OUTCOMES {
+            for b in [1u64, 1_000, 1_000_000, 1_000_000_000_000] {
+                let start = lmsr_cost_fp(&vec![0; n], b).unwrap();
+                assert!(lmsr_subsidy(b, n as u8).unwrap() as u128 * FP_SCALE > start);
+            }
+        }
+        assert!(lmsr_cost_fp(&[0, 0], 0).is_err());
+    }
+
+    #[test]
+    fn lmsr_round_trips_never_pay_out_more_than_paid_in() {
+        let mut rng = Rng(0x0123_4567_89ab_cdef);
+        for _ in 0..300 {
+            let n = 2 + rng.below(MAX_OUTCOMES as u64 - 1) as usize;
+            let b = 100 + rng.below(1_000_000_000);
+            let mut shares = vec![0u64; n];
+            let mut held = vec![0u64; n];
+            let mut paid = 0u64;
+
+            for _ in 0..1 + rng.below(10) {
+                let i = rng.below(n as u64) as usize;
+                let amount = 1 + rng.below(4 * b);
+                paid += lmsr_buy(&mut shares, b, i, amount).unwrap();
+                held[i] += amount;
+            }
+
+            // Sell everything back in random chunks
+            let mut received = 0u64;
+            while let Some(i) = (0..n).find(|&i| held[i] > 0) {
+                let amount = 1 + rng.below(held[i]);
+                received += lmsr_sell(&mut shares, b, i, amount).unwrap();
+                held[i] -= amount;
+            }
+
+            assert!(received <= paid, "received {received} > paid {paid}");
+            assert_eq!(shares, vec![0; n]);
+        }
+    }
+
+    #[test]
+    fn lmsr_vault_always_covers_the_winning_outcome() {
+        let mut rng = Rng(0xfeed_face_0bad_f00d);
+        for _ in 0..300 {
+            let n = 2 + rng.below(MAX_OUTCOMES as u64 - 1) as usize;
+            let b = 1 + rng.below(1_000_000_000);
+            let mut shares = vec![0u64; n];
+            let mut vault = lmsr_subsidy(b, n as u8).unwrap();
+
+            for _ in 0..50 {
+                let i = rng.below(n as u64) as usize;
+                if shares[i] > 0 && rng.below(3) == 0 {
+                    let amount = 1 + rng.below(shares[i]);
+                    vault -= lmsr_sell(&mut shares, b, i, amount).unwrap();
+                } else {
+                    let amount = 1 + rng.below(2 * b);
+                    vault += lmsr_buy(&mut shares, b, i, amount).unwrap();
+                }
+
+                // Whichever outcome wins, every share redeems for one unit
+                assert!(shares.iter().all(|&q| vault >= q), "{vault} < {shares:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn ledger_counters_track_open_positions() {
+        let mut rng = Rng(0x5eed_1e55_c0de_cafe);
+        let mut ledgers = empty_ledgers();
+        let mut bets: Vec<Bet> = (0..8).map(|_| empty_bet()).collect();
+
+        for _ in 0..2_000 {
+            let bet = &mut bets[rng.below(8) as usize];
+            let i = rng.below(4) as usize;
+            if bet.outcome_amounts[i] > 0 && rng.below(2) == 0 {
+                // Partial or full exit; like an LMSR sale, the basis released
+                // need not match what this outcome's shard took in
+                let units = 1 + rng.below(bet.outcome_amounts[i]);
+                let last = bet.outcome_amounts.iter().filter(|&&held| held > 0).count() == 1;
+                let basis = if units == bet.outcome_amounts[i] && last {
+                    bet.amount
+                } else {
+                    rng.below(bet.amount + 1)
+                };
+                close_stake(&mut ledgers[i], bet, i, units, basis).unwrap();
+            } else {
+                let units = 1 + rng.below(1_000);
+                let basis = rng.below(1_000);
+                open_stake(&mut ledgers[i], bet, i, units, basis).unwrap();
+            }
+
+            let totals = pool_totals(&ledgers).unwrap();
+            let open = bets.iter().filter(|bet| !position_is_empty(bet)).count();
+            assert_eq!(totals.total_bettors as usize, open);
+            for i in 0..MAX_OUTCOMES {
+                let holders = bets.iter().filter(|bet| bet.outcome_amounts[i] > 0).count();
+                assert_eq!(totals.bettor_counts[i] as usize, holders);
+            }
+            let basis: u64 = bets.iter().map(|bet| bet.amount).sum();
+            assert_eq!(totals.open_interest, basis);
+        }
+    }
+}
+